@@ -0,0 +1,244 @@
+//! Derive macros for the `LambdaEncode` and `LambdaDecode` traits of
+//! [blc](https://docs.rs/blc); they are re-exported by it with its `derive` feature.
+//!
+//! Structs are encoded as Church tuples of their fields (`λ1 a b ..`) and enums as Scott-encoded
+//! sums: one abstraction per variant, with the body applying the abstraction of the encoded
+//! variant to its fields (e.g. `λλλ2 a b` for the second of three variants). The encoding can be
+//! chosen with the `lambda` attribute:
+//!
+//! | attribute                            | applies to         | encoding                        |
+//! |--------------------------------------|--------------------|---------------------------------|
+//! | `#[lambda(encoding = "tuple")]`      | structs            | a Church tuple (the default)    |
+//! | `#[lambda(encoding = "transparent")]`| single-field structs | the field itself              |
+//! | `#[lambda(encoding = "scott")]`      | enums              | a Scott-encoded sum (the default) |
+//! | `#[lambda(encoding = "tagged")]`     | enums              | a Church pair of the variant's index (a Church numeral) and a Church tuple of its fields |
+
+#![deny(missing_docs)]
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::{Data, DeriveInput, Fields, Generics, Ident, LitStr, parse_macro_input, parse_quote};
+
+/// Derives `LambdaEncode` for a struct or an enum whose fields implement it.
+#[proc_macro_derive(LambdaEncode, attributes(lambda))]
+pub fn derive_lambda_encode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_encode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `LambdaDecode` for a struct or an enum whose fields implement it.
+#[proc_macro_derive(LambdaDecode, attributes(lambda))]
+pub fn derive_lambda_decode(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand_decode(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Encoding {
+    Tuple,
+    Transparent,
+    Scott,
+    Tagged,
+}
+
+// reads the `lambda` attribute and checks that its encoding applies to the type
+fn encoding(input: &DeriveInput) -> syn::Result<Encoding> {
+    let mut encoding = None;
+
+    for attr in input.attrs.iter().filter(|a| a.path().is_ident("lambda")) {
+        attr.parse_nested_meta(|meta| {
+            if !meta.path.is_ident("encoding") {
+                return Err(meta.error("unsupported `lambda` attribute"));
+            }
+            let value: LitStr = meta.value()?.parse()?;
+            encoding = Some(match value.value().as_str() {
+                "tuple" => Encoding::Tuple,
+                "transparent" => Encoding::Transparent,
+                "scott" => Encoding::Scott,
+                "tagged" => Encoding::Tagged,
+                _ => return Err(syn::Error::new(value.span(), "unknown encoding")),
+            });
+            Ok(())
+        })?;
+    }
+
+    let error = |message| Err(syn::Error::new_spanned(&input.ident, message));
+    match (&input.data, encoding) {
+        (Data::Struct(_), None) => Ok(Encoding::Tuple),
+        (Data::Enum(_), None) => Ok(Encoding::Scott),
+        (Data::Struct(data), Some(Encoding::Transparent)) if data.fields.len() != 1 => {
+            error("a transparent encoding requires exactly one field")
+        }
+        (Data::Struct(_), Some(encoding @ (Encoding::Tuple | Encoding::Transparent)))
+        | (Data::Enum(_), Some(encoding @ (Encoding::Scott | Encoding::Tagged))) => Ok(encoding),
+        (Data::Struct(_), Some(_)) => {
+            error("structs can only be encoded as tuples or transparently")
+        }
+        (Data::Enum(_), Some(_)) => error("enums can only be encoded as Scott or tagged sums"),
+        (Data::Union(_), _) => error("unions can't be lambda-encoded"),
+    }
+}
+
+// adds a bound on the given trait to all the type parameters
+fn bounded(generics: &Generics, bound: TokenStream2) -> Generics {
+    let mut generics = generics.clone();
+    let params = generics
+        .type_params()
+        .map(|p| p.ident.clone())
+        .collect::<Vec<_>>();
+
+    let where_clause = generics.make_where_clause();
+    for param in params {
+        where_clause.predicates.push(parse_quote!(#param: #bound));
+    }
+
+    generics
+}
+
+// the pattern binding the fields to the given identifiers and the identifiers
+fn bindings(fields: &Fields) -> (TokenStream2, Vec<Ident>) {
+    let names = (0..fields.len())
+        .map(|i| format_ident!("field_{}", i))
+        .collect::<Vec<_>>();
+
+    let pattern = match fields {
+        Fields::Named(named) => {
+            let members = named.named.iter().map(|f| &f.ident);
+            quote!({ #(#members: #names),* })
+        }
+        Fields::Unnamed(_) => quote!(( #(#names),* )),
+        Fields::Unit => quote!(),
+    };
+
+    (pattern, names)
+}
+
+fn expand_encode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let encoding = encoding(input)?;
+    let lambda = quote!(::blc::encoding::lambda);
+    let name = &input.ident;
+    let generics = bounded(&input.generics, quote!(#lambda::LambdaEncode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let (pattern, names) = bindings(&data.fields);
+            let encoded = match encoding {
+                Encoding::Transparent => quote!(#lambda::LambdaEncode::lambda_encode(#(#names)*)),
+                _ => {
+                    quote!(#lambda::tuple(vec![#(#lambda::LambdaEncode::lambda_encode(#names)),*]))
+                }
+            };
+            quote! {
+                let #name #pattern = self;
+                #encoded
+            }
+        }
+        Data::Enum(data) => {
+            let variants = data.variants.len();
+            let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+                let ident = &variant.ident;
+                let (pattern, names) = bindings(&variant.fields);
+                let fields = quote!(vec![#(#lambda::LambdaEncode::lambda_encode(#names)),*]);
+                let encoded = match encoding {
+                    Encoding::Tagged => quote!(#lambda::tag(#index, #fields)),
+                    _ => quote!(#lambda::sum(#variants, #index, #fields)),
+                };
+                quote!(#name::#ident #pattern => #encoded)
+            });
+            quote! {
+                match self {
+                    #(#arms,)*
+                }
+            }
+        }
+        Data::Union(_) => unreachable!(), // rejected by `encoding`
+    };
+
+    Ok(quote! {
+        impl #impl_generics #lambda::LambdaEncode for #name #ty_generics #where_clause {
+            fn lambda_encode(&self) -> ::blc::lambda_calculus::Term {
+                #body
+            }
+        }
+    })
+}
+
+// the expression constructing a struct or a variant from an iterator of encoded `fields`
+fn construct(path: TokenStream2, fields: &Fields) -> TokenStream2 {
+    let lambda = quote!(::blc::encoding::lambda);
+    let decoded = (0..fields.len())
+        .map(|_| quote!(#lambda::LambdaDecode::lambda_decode(fields.next().unwrap())?))
+        .collect::<Vec<_>>();
+
+    match fields {
+        Fields::Named(named) => {
+            let members = named.named.iter().map(|f| &f.ident);
+            quote!(#path { #(#members: #decoded),* })
+        }
+        Fields::Unnamed(_) => quote!(#path( #(#decoded),* )),
+        Fields::Unit => path,
+    }
+}
+
+fn expand_decode(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let encoding = encoding(input)?;
+    let lambda = quote!(::blc::encoding::lambda);
+    let name = &input.ident;
+    let generics = bounded(&input.generics, quote!(#lambda::LambdaDecode));
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let arity = data.fields.len();
+            let fields = match encoding {
+                Encoding::Transparent => quote!(vec![term]),
+                _ => quote!(#lambda::untuple(term, #arity)?),
+            };
+            let constructed = construct(quote!(#name), &data.fields);
+            quote! {
+                let mut fields = #fields.into_iter();
+                Ok(#constructed)
+            }
+        }
+        Data::Enum(data) => {
+            let arities = data.variants.iter().map(|v| v.fields.len());
+            let split = match encoding {
+                Encoding::Tagged => quote!(#lambda::untag),
+                _ => quote!(#lambda::unsum),
+            };
+            let arms = data.variants.iter().enumerate().map(|(index, variant)| {
+                let ident = &variant.ident;
+                let constructed = construct(quote!(#name::#ident), &variant.fields);
+                quote!(#index => Ok(#constructed))
+            });
+            quote! {
+                let (index, fields) = #split(term, &[#(#arities),*])?;
+                let mut fields = fields.into_iter();
+                match index {
+                    #(#arms,)*
+                    _ => unreachable!(),
+                }
+            }
+        }
+        Data::Union(_) => unreachable!(), // rejected by `encoding`
+    };
+
+    Ok(quote! {
+        impl #impl_generics #lambda::LambdaDecode for #name #ty_generics #where_clause {
+            #[allow(unused_mut, unused_variables)]
+            fn lambda_decode(
+                term: ::blc::lambda_calculus::Term,
+            ) -> Result<Self, #lambda::Error> {
+                #body
+            }
+        }
+    })
+}
@@ -0,0 +1,175 @@
+//! Static analysis of lambda terms
+//!
+//! `analyze` gathers the properties of a term that can be determined without reducing it: whether
+//! it is closed and well-formed, how large it is (both in nodes and in BLC bits) and whether it is
+//! already in its (head) normal form.
+
+use lambda_calculus::*;
+
+/// The static properties of a lambda `Term`.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Analysis {
+    /// the free variables, as seen from outside of the term, in ascending order
+    pub free_variables: Vec<usize>,
+    /// the largest De Bruijn index
+    pub max_index: usize,
+    /// the number of ill-formed variables (with the index 0)
+    pub ill_formed: usize,
+    /// the size of the BLC encoding in bits
+    pub size: usize,
+    /// the number of variables
+    pub variables: usize,
+    /// the number of abstractions
+    pub abstractions: usize,
+    /// the number of applications
+    pub applications: usize,
+    /// the height of the syntax tree
+    pub depth: usize,
+    /// the number of β-redexes
+    pub redexes: usize,
+    /// whether the term is in head normal form
+    pub is_head_normal: bool,
+}
+
+impl Analysis {
+    /// Returns `true` if the term has no free variables.
+    pub fn is_closed(&self) -> bool {
+        self.free_variables.is_empty()
+    }
+
+    /// Returns `true` if all the variables of the term have valid (positive) indices.
+    pub fn is_well_formed(&self) -> bool {
+        self.ill_formed == 0
+    }
+
+    /// Returns `true` if the term is in normal form.
+    pub fn is_normal(&self) -> bool {
+        self.redexes == 0
+    }
+
+    /// Returns the total number of nodes of the term.
+    pub fn nodes(&self) -> usize {
+        self.variables + self.abstractions + self.applications
+    }
+}
+
+/// Analyzes a lambda `Term`.
+///
+/// # Example
+/// ```
+/// use blc::analysis::analyze;
+/// use lambda_calculus::*;
+///
+/// // λ(λ1) 3
+/// let analysis = analyze(&abs(app(abs(Var(1)), Var(3))));
+///
+/// assert_eq!(analysis.free_variables, vec![2]);
+/// assert_eq!(analysis.size, 12);
+/// assert_eq!(analysis.nodes(), 5);
+/// assert_eq!(analysis.depth, 4);
+/// assert_eq!(analysis.redexes, 1);
+/// assert!(!analysis.is_head_normal);
+/// ```
+pub fn analyze(term: &Term) -> Analysis {
+    let mut analysis = Analysis {
+        is_head_normal: is_head_normal(term),
+        ..Analysis::default()
+    };
+
+    // terms along with the number of abstractions above them and their own depth
+    let mut stack = vec![(term, 0, 1)];
+    while let Some((term, binders, depth)) = stack.pop() {
+        analysis.depth = analysis.depth.max(depth);
+
+        match term {
+            Var(i) => {
+                analysis.variables += 1;
+                analysis.size += i + 1;
+                analysis.max_index = analysis.max_index.max(*i);
+                if *i == 0 {
+                    analysis.ill_formed += 1;
+                } else if *i > binders {
+                    analysis.free_variables.push(i - binders);
+                }
+            }
+            Abs(t) => {
+                analysis.abstractions += 1;
+                analysis.size += 2;
+                stack.push((t, binders + 1, depth + 1));
+            }
+            App(boxed) => {
+                analysis.applications += 1;
+                analysis.size += 2;
+                if let Abs(_) = boxed.0 {
+                    analysis.redexes += 1;
+                }
+                stack.push((&boxed.1, binders, depth + 1));
+                stack.push((&boxed.0, binders, depth + 1));
+            }
+        }
+    }
+
+    analysis.free_variables.sort_unstable();
+    analysis.free_variables.dedup();
+
+    analysis
+}
+
+// a term is in head normal form if it is of the form `λ…λ. x M₁ … Mₙ`
+fn is_head_normal(term: &Term) -> bool {
+    let mut term = term;
+    while let Abs(t) = term {
+        term = t;
+    }
+    while let App(boxed) = term {
+        term = &boxed.0;
+    }
+
+    matches!(term, Var(_))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::binary::{from_bits, to_bits};
+    use lambda_calculus::combinators::{I, Y};
+
+    #[test]
+    fn closedness() {
+        assert!(analyze(&I()).is_closed());
+        assert!(analyze(&Y()).is_closed());
+        assert_eq!(analyze(&Var(1)).free_variables, vec![1]);
+        assert_eq!(
+            analyze(&abs(app!(Var(3), Var(1), abs(Var(4)), Var(2)))).free_variables,
+            vec![1, 2]
+        );
+    }
+
+    #[test]
+    fn ill_formed_indices() {
+        let analysis = analyze(&abs(app(Var(0), Var(1))));
+
+        assert_eq!(analysis.ill_formed, 1);
+        assert!(!analysis.is_well_formed());
+        assert!(analysis.is_closed());
+    }
+
+    #[test]
+    fn sizes() {
+        let blc = b"000101100100011010000000000001011011110010111100111111011111011010";
+        let term = from_bits(blc).unwrap();
+        let analysis = analyze(&term);
+
+        assert_eq!(analysis.size, blc.len());
+        assert_eq!(analysis.size, to_bits(&term).len());
+        assert_eq!(analysis.max_index, 5);
+    }
+
+    #[test]
+    fn normal_forms() {
+        assert!(analyze(&I()).is_normal());
+        assert!(analyze(&abs(app(Var(1), app(I(), Var(1))))).is_head_normal);
+        assert!(!analyze(&abs(app(Var(1), app(I(), Var(1))))).is_normal());
+        assert!(!analyze(&app(Y(), I())).is_head_normal);
+    }
+}
@@ -0,0 +1,292 @@
+//! Differential testing of reduction strategies and backends
+//!
+//! Normalizing reduction strategies (and correct evaluators in general) agree on the normal form
+//! of every term that has one. This module runs a program through a set of `Evaluator`s, each of
+//! them limited by a reduction budget, and compares their results with the ones of the reference
+//! evaluator - plain normal-order reduction. Evaluators that don't reach a normal form within the
+//! budget (e.g. applicative-order reduction of a term built on a recursion combinator) are not
+//! considered to disagree with the reference, but ones that panic are.
+//!
+//! The evaluators cover the reduction strategies, accelerated arithmetic, caching and the BCL
+//! backend. The Lazy K and interactive runners (`execution::lazy_k`, `execution::effect`) aren't
+//! compared: they don't produce normal forms, but streams of output that are reduced to head
+//! normal forms one element at a time and may never end. When the evaluators disagree, `minimize`
+//! shrinks the input; the program is left as it is.
+
+use crate::encoding::bcl;
+use crate::encoding::lambda::{decode, encode};
+use crate::execution::arithmetic;
+use crate::execution::cache::{Cache, is_normal};
+use lambda_calculus::reduction::Order;
+use lambda_calculus::*;
+use std::panic::{self, AssertUnwindSafe};
+use std::thread;
+
+/// The stack size of the thread performing the reductions; the deeper the reduced terms get, the
+/// more of it is needed.
+const STACK_SIZE: usize = 256 * 1024 * 1024;
+
+/// A means of reducing lambda terms to their normal forms.
+pub trait Evaluator: Sync {
+    /// Returns the name of the evaluator.
+    fn name(&self) -> String;
+
+    /// Reduces the `Term`, performing at most `budget` reductions.
+    fn reduce(&self, term: &mut Term, budget: usize);
+}
+
+/// Plain β-reduction with the given evaluation `Order`.
+pub struct Strategy(pub Order);
+
+impl Evaluator for Strategy {
+    fn name(&self) -> String {
+        self.0.to_string()
+    }
+
+    fn reduce(&self, term: &mut Term, budget: usize) {
+        term.reduce(self.0, budget);
+    }
+}
+
+/// Normal-order reduction with accelerated Church numeral arithmetic.
+pub struct Arithmetic;
+
+impl Evaluator for Arithmetic {
+    fn name(&self) -> String {
+        "normal, arithmetic".into()
+    }
+
+    fn reduce(&self, term: &mut Term, budget: usize) {
+        arithmetic::reduce(term, budget);
+    }
+}
+
+/// Normal-order reduction preceded by the substitution of normal forms of closed subterms, as
+/// performed by a fresh `Cache`.
+pub struct Cached;
+
+impl Evaluator for Cached {
+    fn name(&self) -> String {
+        "normal, cached".into()
+    }
+
+    fn reduce(&self, term: &mut Term, budget: usize) {
+        let spent = Cache::new().limit(budget).normalize_within(term, budget);
+        if spent < budget {
+            term.reduce(NOR, budget - spent);
+        }
+    }
+}
+
+/// Normal-order reduction of the term converted to BCL and back, the way BCL programs are executed
+/// (see `execution::run_bcl`); open terms, which BCL can't represent, are reduced as they are.
+pub struct Bcl;
+
+impl Evaluator for Bcl {
+    fn name(&self) -> String {
+        "normal, BCL".into()
+    }
+
+    fn reduce(&self, term: &mut Term, budget: usize) {
+        if let Ok(combinator) = bcl::from_lambda(term) {
+            *term = bcl::to_lambda(&combinator);
+        }
+        term.reduce(NOR, budget);
+    }
+}
+
+/// Returns all the available evaluators, starting with the reference one.
+pub fn evaluators() -> Vec<Box<dyn Evaluator>> {
+    vec![
+        Box::new(Strategy(NOR)),
+        Box::new(Strategy(HNO)),
+        Box::new(Strategy(HAP)),
+        Box::new(Strategy(APP)),
+        Box::new(Arithmetic),
+        Box::new(Cached),
+        Box::new(Bcl),
+    ]
+}
+
+/// The result of a single evaluator.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Outcome {
+    /// the name of the evaluator
+    pub evaluator: String,
+    /// the normal form, if it was reached within the budget
+    pub normal_form: Option<Term>,
+    /// the decoded normal form
+    pub output: Option<String>,
+    /// whether the evaluator panicked
+    pub panicked: bool,
+}
+
+/// The results of all the evaluators, the first of them being the reference one.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Report {
+    /// the results in the order of the evaluators
+    pub outcomes: Vec<Outcome>,
+}
+
+impl Report {
+    /// Returns the outcomes of the evaluators that panicked or disagree with the reference one,
+    /// either in the normal form or in the decoded output.
+    pub fn disagreements(&self) -> Vec<&Outcome> {
+        let Some(reference) = self.outcomes.first() else {
+            return Vec::new();
+        };
+
+        self.outcomes
+            .iter()
+            .filter(|outcome| {
+                outcome.panicked
+                    || reference.normal_form.is_some() // otherwise inconclusive
+                        && outcome.normal_form.is_some()
+                        && (outcome.normal_form != reference.normal_form
+                            || outcome.output != reference.output)
+            })
+            .collect()
+    }
+
+    /// Returns `true` if none of the evaluators disagrees with the reference one.
+    pub fn is_consistent(&self) -> bool {
+        self.disagreements().is_empty()
+    }
+}
+
+/// Evaluates a program applied to the given (lambda-encoded) input, if there is one, with all the
+/// given evaluators and reports their results.
+///
+/// # Example
+/// ```
+/// use blc::differential::{check, evaluators};
+/// use blc::from_bits;
+///
+/// let reverse = from_bits(b"0001011001000110100000000001011100111110111100001011011110110000010");
+/// let report = check(&evaluators(), &reverse.unwrap(), Some(b"herp"), 1 << 12);
+///
+/// assert!(report.is_consistent());
+/// assert_eq!(report.outcomes[0].output, Some("preh".into()));
+/// ```
+pub fn check(
+    evaluators: &[Box<dyn Evaluator>],
+    program: &Term,
+    input: Option<&[u8]>,
+    budget: usize,
+) -> Report {
+    let term = match input {
+        Some(input) => app(program.clone(), encode(input)),
+        None => program.clone(),
+    };
+
+    // the evaluators take turns on a single thread with a large stack
+    let outcomes = thread::scope(|scope| {
+        thread::Builder::new()
+            .stack_size(STACK_SIZE)
+            .spawn_scoped(scope, || {
+                evaluators
+                    .iter()
+                    .map(|evaluator| {
+                        let mut term = term.clone();
+                        let reduced = panic::catch_unwind(AssertUnwindSafe(|| {
+                            evaluator.reduce(&mut term, budget)
+                        }));
+                        outcome(evaluator.name(), reduced.ok().map(|_| term))
+                    })
+                    .collect()
+            })
+            .expect("failed to spawn the evaluator thread")
+            .join()
+            .unwrap() // safe; the panics of the evaluators are caught
+    });
+
+    Report { outcomes }
+}
+
+// the outcome of an evaluator that reduced a term to the given one or, if there is none, panicked
+fn outcome(evaluator: String, term: Option<Term>) -> Outcome {
+    match term {
+        Some(term) if is_normal(&term) => Outcome {
+            evaluator,
+            output: decode(term.clone()).ok(),
+            normal_form: Some(term),
+            panicked: false,
+        },
+        _ => Outcome {
+            evaluator,
+            normal_form: None,
+            output: None,
+            panicked: term.is_none(),
+        },
+    }
+}
+
+/// Shrinks the input of a program for which the evaluators disagree to one for which they still
+/// do, removing as many bytes as possible. Returns `None` if they don't disagree to begin with.
+///
+/// # Example
+/// ```
+/// use blc::differential::{minimize, Evaluator, Strategy};
+/// use blc::encoding::lambda::decode;
+/// use blc::from_bits;
+/// use lambda_calculus::*;
+///
+/// // an evaluator that mishandles null bytes
+/// struct Broken;
+///
+/// impl Evaluator for Broken {
+///     fn name(&self) -> String {
+///         "broken".into()
+///     }
+///
+///     fn reduce(&self, term: &mut Term, budget: usize) {
+///         term.reduce(NOR, budget);
+///         if decode(term.clone()).is_ok_and(|output| output.contains('\0')) {
+///             *term = Var(1);
+///         }
+///     }
+/// }
+///
+/// let evaluators: Vec<Box<dyn Evaluator>> = vec![Box::new(Strategy(NOR)), Box::new(Broken)];
+/// let identity = from_bits(b"0010").unwrap();
+///
+/// assert_eq!(minimize(&evaluators, &identity, b"abc", 1 << 10), None);
+/// assert_eq!(minimize(&evaluators, &identity, b"ab\x00c", 1 << 10), Some(b"\x00".to_vec()));
+/// ```
+pub fn minimize(
+    evaluators: &[Box<dyn Evaluator>],
+    program: &Term,
+    input: &[u8],
+    budget: usize,
+) -> Option<Vec<u8>> {
+    let fails = |input: &[u8]| !check(evaluators, program, Some(input), budget).is_consistent();
+
+    if !fails(input) {
+        return None;
+    }
+
+    let mut input = input.to_vec();
+    let mut chunk = input.len().div_ceil(2);
+    while chunk > 0 {
+        let mut start = 0;
+        let mut removed = false;
+
+        while start < input.len() {
+            let end = (start + chunk).min(input.len());
+            let candidate = [&input[..start], &input[end..]].concat();
+
+            if fails(&candidate) {
+                input = candidate;
+                removed = true;
+            } else {
+                start += chunk;
+            }
+        }
+
+        if !removed {
+            chunk /= 2;
+        }
+    }
+
+    Some(input)
+}
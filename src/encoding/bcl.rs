@@ -0,0 +1,502 @@
+//! Binary combinatory logic (BCL)
+//!
+//! BCL is the combinatory-logic sibling of binary lambda calculus: its terms are built from the
+//! combinators `S` and `K` and their applications, represented as follows:
+//!
+//! | term        | BCL  |
+//! |-------------|------|
+//! | K           | 00   |
+//! | S           | 01   |
+//! | application | 1MN  |
+//!
+//! Lambda terms are converted to BCL with bracket abstraction and BCL terms are converted back by
+//! substituting `S` with `λλλ31(21)` and `K` with `λλ2` and contracting the redexes that are safe
+//! to contract, which is how BCL programs are executed (see `execution::run_bcl`).
+
+use self::Combinator::*;
+use self::Error::*;
+use lambda_calculus::{Term, Term::Var, abs, app};
+use std::{error, fmt};
+
+/// A combinatory logic term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Combinator {
+    /// the `S` combinator: `S x y z = x z (y z)`
+    S,
+    /// the `K` combinator: `K x y = x`
+    K,
+    /// an application
+    App(Box<(Combinator, Combinator)>),
+}
+
+/// An error that can occur when parsing BCL or converting lambda terms to it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// the input ended before the term was complete
+    UnexpectedEof {
+        /// the offset of the missing bit in the input
+        position: usize,
+    },
+    /// a character that is neither a bit nor whitespace
+    InvalidCharacter {
+        /// the offset of the character in the input
+        position: usize,
+        /// the character that was found instead of a bit
+        found: u8,
+    },
+    /// a lambda term that isn't closed or has a variable with index 0
+    OpenTerm,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnexpectedEof { position } => write!(f, "unexpected EOF at bit {}", position),
+            InvalidCharacter { position, found } => write!(
+                f,
+                "invalid character {:?} at bit {}",
+                char::from(*found),
+                position
+            ),
+            OpenTerm => write!(f, "the lambda term is not closed"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl Combinator {
+    /// Applies the combinator to another one.
+    pub fn app(self, argument: Combinator) -> Combinator {
+        App(Box::new((self, argument)))
+    }
+}
+
+impl fmt::Display for Combinator {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        enum Item<'a> {
+            Term(&'a Combinator),
+            Str(&'static str),
+        }
+
+        let mut stack = vec![Item::Term(self)];
+        while let Some(item) = stack.pop() {
+            match item {
+                Item::Term(S) => write!(f, "S")?,
+                Item::Term(K) => write!(f, "K")?,
+                Item::Term(App(boxed)) => {
+                    if let App(_) = boxed.1 {
+                        stack.push(Item::Str(")"));
+                        stack.push(Item::Term(&boxed.1));
+                        stack.push(Item::Str("("));
+                    } else {
+                        stack.push(Item::Term(&boxed.1));
+                    }
+                    stack.push(Item::Term(&boxed.0));
+                }
+                Item::Str(s) => f.write_str(s)?,
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Parse a BCL-encoded `Combinator`, ignoring whitespace; data following it is ignored.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bcl::from_bits;
+///
+/// assert_eq!(from_bits(b"1 1 01 00 00").unwrap().to_string(), "SKK");
+/// ```
+pub fn from_bits(input: &[u8]) -> Result<Combinator, Error> {
+    from_bits_prefix(input).map(|(term, _)| term)
+}
+
+/// Parse a BCL-encoded `Combinator` from the beginning of the input, returning it along with the
+/// rest of the input.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bcl::{from_bits_prefix, Combinator};
+///
+/// assert_eq!(from_bits_prefix(b"0110"), Ok((Combinator::S, &b"10"[..])));
+/// ```
+pub fn from_bits_prefix(input: &[u8]) -> Result<(Combinator, &[u8]), Error> {
+    enum Frame {
+        AppLhs,
+        AppRhs(Combinator),
+    }
+
+    let mut position = 0;
+    let mut bit = || loop {
+        let Some(&found) = input.get(position) else {
+            return Err(UnexpectedEof { position });
+        };
+        position += 1;
+
+        match found {
+            b'0' => return Ok(false),
+            b'1' => return Ok(true),
+            b'\t' | b'\n' | b'\r' | b' ' => {}
+            _ => {
+                return Err(InvalidCharacter {
+                    position: position - 1,
+                    found,
+                });
+            }
+        }
+    };
+
+    let mut stack = Vec::new();
+    let term = 'parse: loop {
+        if bit()? {
+            stack.push(Frame::AppLhs);
+            continue;
+        }
+        let mut term = if bit()? { S } else { K };
+
+        loop {
+            match stack.pop() {
+                None => break 'parse term,
+                Some(Frame::AppLhs) => {
+                    stack.push(Frame::AppRhs(term));
+                    break;
+                }
+                Some(Frame::AppRhs(lhs)) => term = lhs.app(term),
+            }
+        }
+    };
+
+    Ok((term, &input[position..]))
+}
+
+/// Represent a `Combinator` in BCL.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bcl::{to_bits, Combinator::*};
+///
+/// assert_eq!(to_bits(&S.app(K).app(K)), b"11010000");
+/// ```
+pub fn to_bits(term: &Combinator) -> Vec<u8> {
+    let mut output = Vec::new();
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        match term {
+            S => output.extend_from_slice(b"01"),
+            K => output.extend_from_slice(b"00"),
+            App(boxed) => {
+                output.push(b'1');
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
+        }
+    }
+
+    output
+}
+
+// a combinatory logic term that can also contain free variables, as De Bruijn levels (i.e. counting
+// the abstractions from the outermost one); applications are annotated with their greatest level
+enum Open {
+    Closed(Combinator),
+    Var(usize),
+    App(Box<(Open, Open)>, usize),
+}
+
+impl Open {
+    fn app(self, argument: Open) -> Open {
+        match (self, argument) {
+            (Open::Closed(lhs), Open::Closed(rhs)) => Open::Closed(lhs.app(rhs)),
+            (lhs, rhs) => {
+                let level = lhs.level().max(rhs.level());
+                Open::App(Box::new((lhs, rhs)), level)
+            }
+        }
+    }
+
+    // the greatest level of a variable in the term; 0 if it is closed
+    fn level(&self) -> usize {
+        match self {
+            Open::Closed(_) => 0,
+            Open::Var(level) | Open::App(_, level) => *level,
+        }
+    }
+
+    // whether the term is known to stand for an abstraction: closed terms (with normal forms) and
+    // combinators applied to fewer arguments than they take are
+    fn is_abstraction(&self) -> bool {
+        let mut args = 0;
+        let mut head = self;
+        while let Open::App(boxed, _) = head {
+            args += 1;
+            head = &boxed.0;
+        }
+
+        match head {
+            Open::Closed(_) if args == 0 => true,
+            Open::Closed(combinator) => {
+                let mut head = combinator;
+                while let App(boxed) = head {
+                    args += 1;
+                    head = &boxed.0;
+                }
+                match head {
+                    S => args < 3,
+                    K => args < 2,
+                    App(_) => unreachable!(),
+                }
+            }
+            _ => false,
+        }
+    }
+
+    // removes the variable of the given level, which has to be the greatest one in the term, i.e.
+    // performs bracket abstraction over it
+    fn abstract_over(self, level: usize) -> Open {
+        enum Task {
+            // a term, and whether it is the whole abstracted one
+            Visit(Open, bool),
+            Build,
+        }
+
+        let mut tasks = vec![Task::Visit(self, true)];
+        let mut terms = Vec::new();
+
+        while let Some(task) = tasks.pop() {
+            match task {
+                Task::Visit(term, _) if term.level() < level => {
+                    terms.push(Open::Closed(K).app(term));
+                }
+                Task::Visit(Open::App(boxed, _), is_root) => {
+                    let (lhs, rhs) = *boxed;
+                    // η-reduction; the whole term is only reduced if it stays an abstraction, as
+                    // its normal form would change otherwise (parts of it are always applied)
+                    if matches!(rhs, Open::Var(l) if l == level)
+                        && lhs.level() < level
+                        && (!is_root || lhs.is_abstraction())
+                    {
+                        terms.push(lhs);
+                    } else {
+                        tasks.push(Task::Build);
+                        tasks.push(Task::Visit(rhs, false));
+                        tasks.push(Task::Visit(lhs, false));
+                    }
+                }
+                Task::Visit(_, _) => terms.push(Open::Closed(S.app(K).app(K))), // the variable
+                Task::Build => {
+                    let rhs = terms.pop().unwrap(); // safe; both sides were built
+                    let lhs = terms.pop().unwrap();
+                    terms.push(Open::Closed(S).app(lhs).app(rhs));
+                }
+            }
+        }
+
+        terms.pop().unwrap() // safe; the root was built last
+    }
+}
+
+/// Convert a closed lambda `Term` to a `Combinator` using bracket abstraction.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bcl::from_lambda;
+/// use lambda_calculus::combinators::{I, K, S};
+///
+/// assert_eq!(from_lambda(&I()).unwrap().to_string(), "SKK");
+/// assert_eq!(from_lambda(&K()).unwrap().to_string(), "K");
+/// assert_eq!(from_lambda(&S()).unwrap().to_string(), "S");
+/// ```
+pub fn from_lambda(term: &Term) -> Result<Combinator, Error> {
+    enum Task<'a> {
+        Visit(&'a Term, usize),
+        Abstract(usize),
+        Apply,
+    }
+
+    let mut tasks = vec![Task::Visit(term, 0)];
+    let mut terms = Vec::new();
+
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(Term::Var(i), depth) => {
+                if *i == 0 || *i > depth {
+                    return Err(OpenTerm);
+                }
+                terms.push(Open::Var(depth - i + 1));
+            }
+            Task::Visit(Term::Abs(t), depth) => {
+                tasks.push(Task::Abstract(depth + 1));
+                tasks.push(Task::Visit(t, depth + 1));
+            }
+            Task::Visit(Term::App(boxed), depth) => {
+                tasks.push(Task::Apply);
+                tasks.push(Task::Visit(&boxed.1, depth));
+                tasks.push(Task::Visit(&boxed.0, depth));
+            }
+            Task::Abstract(level) => {
+                let body = terms.pop().unwrap(); // safe; the body was built
+                terms.push(body.abstract_over(level));
+            }
+            Task::Apply => {
+                let rhs = terms.pop().unwrap(); // safe; both sides were built
+                let lhs = terms.pop().unwrap();
+                terms.push(lhs.app(rhs));
+            }
+        }
+    }
+
+    match terms.pop() {
+        Some(Open::Closed(term)) => Ok(term),
+        _ => unreachable!(), // all the variables were checked to be bound
+    }
+}
+
+// the number of occurrences of the variable bound by an abstraction in its body, up to 2
+fn occurrences(body: &Term) -> usize {
+    let mut count = 0;
+    let mut stack = vec![(body, 1)];
+
+    while let Some((term, index)) = stack.pop() {
+        match term {
+            Term::Var(i) if *i == index => {
+                count += 1;
+                if count == 2 {
+                    break;
+                }
+            }
+            Term::Var(_) => {}
+            Term::Abs(t) => stack.push((t, index + 1)),
+            Term::App(boxed) => {
+                stack.push((&boxed.1, index));
+                stack.push((&boxed.0, index));
+            }
+        }
+    }
+
+    count
+}
+
+/// Convert a `Combinator` to a lambda `Term`.
+///
+/// `S` and `K` are substituted with `λλλ31(21)` and `λλ2` and the resulting redexes are
+/// contracted wherever that can't duplicate any work, i.e. if the argument is a variable or the
+/// abstraction uses it at most once; this undoes most of the bracket abstraction, so that terms
+/// converted by `from_lambda` reduce in about as many steps as the original ones.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bcl::{to_lambda, Combinator::*};
+/// use lambda_calculus::*;
+///
+/// assert_eq!(to_lambda(&S.app(K).app(K)), abs(Var(1)));
+/// assert_eq!(to_lambda(&S.app(S)), parse("λλλ2 1 (3 2 1)", DeBruijn).unwrap());
+/// ```
+pub fn to_lambda(term: &Combinator) -> Term {
+    enum Task<'a> {
+        Visit(&'a Combinator),
+        Simplify(Term),
+        Abs,
+        App,
+    }
+
+    let mut tasks = vec![Task::Visit(term)];
+    let mut terms = Vec::new();
+
+    while let Some(task) = tasks.pop() {
+        match task {
+            Task::Visit(S) => terms.push(abs!(3, app!(Var(3), Var(1), app(Var(2), Var(1))))),
+            Task::Visit(K) => terms.push(abs!(2, Var(2))),
+            Task::Visit(App(boxed)) => {
+                tasks.push(Task::App);
+                tasks.push(Task::Visit(&boxed.1));
+                tasks.push(Task::Visit(&boxed.0));
+            }
+            Task::Simplify(Term::Abs(t)) => {
+                tasks.push(Task::Abs);
+                tasks.push(Task::Simplify(*t));
+            }
+            Task::Simplify(Term::App(boxed)) => {
+                let (lhs, rhs) = *boxed;
+                tasks.push(Task::App);
+                tasks.push(Task::Simplify(rhs));
+                tasks.push(Task::Simplify(lhs));
+            }
+            Task::Simplify(var) => terms.push(var),
+            Task::Abs => {
+                let body = terms.pop().unwrap(); // safe; the body was built
+                terms.push(abs(body));
+            }
+            Task::App => {
+                let rhs = terms.pop().unwrap(); // safe; both sides were built
+                let mut lhs = terms.pop().unwrap();
+                match lhs.unabs_ref() {
+                    Ok(body) if matches!(rhs, Var(_)) || occurrences(body) < 2 => {
+                        let creates_redexes = matches!(rhs, Term::Abs(_)) && occurrences(body) > 0;
+                        lhs.apply(&rhs).unwrap(); // safe; it's an abstraction
+                        if creates_redexes {
+                            // the argument was substituted for a variable that may be applied
+                            tasks.push(Task::Simplify(lhs));
+                        } else {
+                            terms.push(lhs);
+                        }
+                    }
+                    _ => terms.push(app(lhs, rhs)),
+                }
+            }
+        }
+    }
+
+    terms.pop().unwrap() // safe; the root was built last
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambda_calculus::combinators::{B, C};
+    use lambda_calculus::*;
+
+    #[test]
+    fn parsing_errors() {
+        assert_eq!(from_bits(b""), Err(UnexpectedEof { position: 0 }));
+        assert_eq!(from_bits(b"10"), Err(UnexpectedEof { position: 2 }));
+        assert_eq!(
+            from_bits(b"1 0x"),
+            Err(InvalidCharacter {
+                position: 3,
+                found: b'x'
+            })
+        );
+    }
+
+    #[test]
+    fn printing() {
+        assert_eq!(from_bits(b"11001010000").unwrap().to_string(), "K(SK)K");
+
+        for bits in [&b"00"[..], b"01", b"11010000", b"10110010100"] {
+            assert_eq!(to_bits(&from_bits(bits).unwrap()), bits);
+        }
+    }
+
+    #[test]
+    fn conversion() {
+        for term in [
+            B(),
+            C(),
+            1.into_church(),
+            3.into_church(),
+            abs(app(Var(1), Var(1))),
+        ] {
+            assert_eq!(to_lambda(&from_lambda(&term).unwrap()), term);
+        }
+    }
+
+    #[test]
+    fn open_terms() {
+        assert_eq!(from_lambda(&abs(Var(2))), Err(OpenTerm));
+        assert_eq!(from_lambda(&abs(Var(0))), Err(OpenTerm));
+    }
+}
@@ -1,16 +1,188 @@
 //! Binary encoding for lambda `Term`s
 
 use self::Error::*;
+use crate::encoding::bits::{BitReader, BitWriter};
 use lambda_calculus::term::*;
+use std::io::{self, Read, Write};
+use std::{error, fmt};
 
-/// An error that can occur if the input stream of "bits" is not valid binary lambda calculus.
-#[derive(Debug, PartialEq)]
+/// An error that can occur if the input stream of "bits" is not valid binary lambda calculus or a
+/// term can't be represented in it.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Error {
-    /// not a valid term
-    NotATerm,
+    /// the input ended before the term was complete
+    UnexpectedEof {
+        /// the offset of the missing bit in the input
+        position: usize,
+        /// the expected token
+        expected: Token,
+        /// the innermost term being parsed
+        context: Context,
+    },
+    /// a character that is neither a bit nor whitespace
+    InvalidCharacter {
+        /// the offset of the character in the input
+        position: usize,
+        /// the expected token
+        expected: Token,
+        /// the character that was found instead
+        found: u8,
+    },
+    /// data following a complete term (see `from_bits_strict`)
+    TrailingData {
+        /// the offset of the first character after the term
+        position: usize,
+        /// the character that was found there
+        found: u8,
+    },
+    /// a variable index that doesn't fit in a `usize` (only in the Levenshtein variant)
+    IndexOverflow {
+        /// the offset of the bit at which the index became too large
+        position: usize,
+    },
+    /// a variable with the invalid index 0 (only when representing a term)
+    ZeroIndex {
+        /// the offset at which the variable would have been written
+        position: usize,
+    },
+    /// an I/O error while reading packed bits
+    Io {
+        /// the offset of the bit that couldn't be read
+        position: usize,
+        /// the kind of the error
+        kind: io::ErrorKind,
+    },
 }
 
-/// Parse a blc-encoded lambda `Term`.
+/// A part of a BLC term expected by the parser.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Token {
+    /// the beginning of a term: `00`, `01` or `1`
+    Term,
+    /// the second bit of an abstraction (`0`) or application (`1`)
+    Kind,
+    /// the continuation (`1`) or the end (`0`) of a variable
+    Index,
+}
+
+/// A kind of term that the parser can be inside of.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Context {
+    /// the outermost term
+    Term,
+    /// the body of an abstraction
+    Abstraction,
+    /// an operand of an application
+    Application,
+    /// a variable
+    Variable,
+}
+
+impl Error {
+    /// Returns the offset of the bit at which parsing failed.
+    pub fn position(&self) -> usize {
+        match *self {
+            UnexpectedEof { position, .. }
+            | InvalidCharacter { position, .. }
+            | TrailingData { position, .. }
+            | IndexOverflow { position }
+            | ZeroIndex { position }
+            | Io { position, .. } => position,
+        }
+    }
+
+    // moves the position of the error by the given number of bits
+    fn offset(mut self, by: usize) -> Error {
+        match &mut self {
+            UnexpectedEof { position, .. }
+            | InvalidCharacter { position, .. }
+            | TrailingData { position, .. }
+            | IndexOverflow { position }
+            | ZeroIndex { position }
+            | Io { position, .. } => *position += by,
+        }
+        self
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnexpectedEof {
+                position,
+                expected,
+                context,
+            } => write!(
+                f,
+                "unexpected EOF inside {} at bit {}, expected {}",
+                context, position, expected
+            ),
+            InvalidCharacter {
+                position,
+                expected,
+                found,
+            } => write!(
+                f,
+                "invalid character {:?} at bit {}, expected {}",
+                char::from(*found),
+                position,
+                expected
+            ),
+            TrailingData { position, found } => write!(
+                f,
+                "unexpected {:?} at bit {} after the end of the term",
+                char::from(*found),
+                position
+            ),
+            IndexOverflow { position } => write!(f, "variable index too large at bit {}", position),
+            ZeroIndex { position } => write!(f, "variable with the index 0 at bit {}", position),
+            Io { position, kind } => write!(
+                f,
+                "I/O error at bit {}: {}",
+                position,
+                io::Error::from(*kind)
+            ),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Token::Term => write!(f, "a term"),
+            Token::Kind => write!(f, "'0' (abstraction) or '1' (application)"),
+            Token::Index => write!(f, "'0' or '1' (variable)"),
+        }
+    }
+}
+
+impl fmt::Display for Context {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Context::Term => write!(f, "term"),
+            Context::Abstraction => write!(f, "abstraction"),
+            Context::Application => write!(f, "application"),
+            Context::Variable => write!(f, "variable"),
+        }
+    }
+}
+
+/// A variant of the binary encoding, differing in the way De Bruijn indices are encoded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Variant {
+    /// the standard encoding: the index `i` is encoded as `1`<sup>`i`</sup>`0`
+    #[default]
+    Unary,
+    /// the index `i` is encoded as `1` followed by the
+    /// [Levenshtein code](https://en.wikipedia.org/wiki/Levenshtein_coding) of `i - 1`, which
+    /// makes large indices logarithmic in size; indices `1` and `2` are encoded the same way as in
+    /// the standard encoding
+    Levenshtein,
+}
+
+/// Parse a blc-encoded lambda `Term`. Whitespace between the bits is ignored.
 ///
 /// # Example
 /// ```
@@ -20,57 +192,370 @@ pub enum Error {
 ///
 /// assert!(k.is_ok());
 /// assert_eq!(to_bits(&k.unwrap()), Vec::from(&b"0000110"[..]));
+/// assert_eq!(
+///     from_bits(b"01001").unwrap_err().to_string(),
+///     "unexpected EOF inside variable at bit 5, expected '0' or '1' (variable)"
+/// );
 /// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(size = input.len()))
+)]
 pub fn from_bits(input: &[u8]) -> Result<Term, Error> {
-    if let Some((result, _)) = _from_bits(input) {
-        Ok(result)
-    } else {
-        Err(NotATerm)
+    parse(input, Variant::Unary).map(|(term, _)| term)
+}
+
+/// Parse a lambda `Term` encoded with the given variant of the binary encoding.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{from_bits_with, Variant};
+/// use lambda_calculus::*;
+///
+/// assert_eq!(from_bits_with(b"0011100", Variant::Levenshtein), Ok(abs(Var(3))));
+/// ```
+pub fn from_bits_with(input: &[u8], variant: Variant) -> Result<Term, Error> {
+    parse(input, variant).map(|(term, _)| term)
+}
+
+/// Parse a blc-encoded lambda `Term` from the beginning of the input and return it along with the
+/// rest of the input, which starts right after the term's last bit. Since BLC is self-delimiting,
+/// this can be used to separate a program from the data that follows it.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::from_bits_prefix;
+/// use lambda_calculus::*;
+///
+/// let (term, rest) = from_bits_prefix(b"0010hello").unwrap();
+///
+/// assert_eq!(term, abs(Var(1)));
+/// assert_eq!(rest, b"hello");
+/// ```
+pub fn from_bits_prefix(input: &[u8]) -> Result<(Term, &[u8]), Error> {
+    parse(input, Variant::Unary).map(|(term, length)| (term, &input[length..]))
+}
+
+/// Parse a blc-encoded lambda `Term` like `from_bits`, but fail if anything other than whitespace
+/// follows it.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{from_bits_strict, Error};
+///
+/// assert!(from_bits_strict(b"0010\n").is_ok());
+/// assert_eq!(
+///     from_bits_strict(b"00101"),
+///     Err(Error::TrailingData { position: 4, found: b'1' })
+/// );
+/// ```
+pub fn from_bits_strict(input: &[u8]) -> Result<Term, Error> {
+    let (term, length) = parse(input, Variant::Unary)?;
+
+    match input[length..].iter().position(|b| !WHITESPACE.contains(b)) {
+        Some(i) => Err(TrailingData {
+            position: length + i,
+            found: input[length + i],
+        }),
+        None => Ok(term),
     }
 }
 
-fn _from_bits(input: &[u8]) -> Option<(Term, &[u8])> {
-    if input.is_empty() {
-        return None;
+/// Parse a stream of concatenated blc-encoded lambda `Term`s, optionally separated by whitespace.
+/// The iteration stops after the first error.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::terms;
+/// use lambda_calculus::*;
+///
+/// let parsed = terms(b"0010 000010110").collect::<Result<Vec<_>, _>>();
+///
+/// assert_eq!(parsed, Ok(vec![abs(Var(1)), abs!(2, Var(1)), Var(2)]));
+/// ```
+pub fn terms(input: &[u8]) -> Terms<'_> {
+    Terms {
+        input,
+        position: 0,
+        failed: false,
     }
+}
 
-    if [9, 10, 13, 32].contains(&input[0]) {
-        _from_bits(&input[1..]) // skip whitespaces
-    } else {
-        match &input[0..2] {
-            b"00" => {
-                if let Some((term, rest)) = _from_bits(&input[2..]) {
-                    Some((abs(term), rest))
-                } else {
-                    None
+/// An iterator over concatenated blc-encoded lambda `Term`s, created with `terms`.
+#[derive(Debug, Clone)]
+pub struct Terms<'a> {
+    input: &'a [u8],
+    position: usize,
+    failed: bool,
+}
+
+impl Terms<'_> {
+    /// Returns the part of the input that hasn't been parsed yet.
+    pub fn rest(&self) -> &[u8] {
+        &self.input[self.position..]
+    }
+}
+
+impl Iterator for Terms<'_> {
+    type Item = Result<Term, Error>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.rest().iter().all(|b| WHITESPACE.contains(b)) {
+            return None;
+        }
+
+        match parse(self.rest(), Variant::Unary) {
+            Ok((term, length)) => {
+                self.position += length;
+                Some(Ok(term))
+            }
+            Err(error) => {
+                self.failed = true;
+                Some(Err(error.offset(self.position)))
+            }
+        }
+    }
+}
+
+/// The characters that are ignored between bits.
+pub(crate) const WHITESPACE: [u8; 4] = [9, 10, 13, 32];
+
+// a term whose parsing is in progress
+enum Frame {
+    Abs,
+    AppLhs,
+    AppRhs(Term),
+}
+
+// a source of bits for the parser
+trait Bits {
+    fn bit(&mut self, expected: Token, context: Context) -> Result<bool, Error>;
+
+    fn position(&self) -> usize;
+}
+
+// parses a term from the beginning of the input; returns it along with the length of the consumed
+// input
+fn parse(input: &[u8], variant: Variant) -> Result<(Term, usize), Error> {
+    let mut reader = Reader { input, position: 0 };
+    let term = parse_from(&mut reader, variant)?;
+
+    Ok((term, reader.position))
+}
+
+fn parse_from<B: Bits>(bits: &mut B, variant: Variant) -> Result<Term, Error> {
+    let mut stack = Vec::new();
+
+    loop {
+        let context = match stack.last() {
+            None => Context::Term,
+            Some(Frame::Abs) => Context::Abstraction,
+            Some(_) => Context::Application,
+        };
+
+        let mut term = if bits.bit(Token::Term, context)? {
+            Var(parse_index(bits, variant)?)
+        } else {
+            stack.push(if bits.bit(Token::Kind, context)? {
+                Frame::AppLhs
+            } else {
+                Frame::Abs
+            });
+            continue;
+        };
+
+        loop {
+            match stack.pop() {
+                None => return Ok(term),
+                Some(Frame::Abs) => term = abs(term),
+                Some(Frame::AppLhs) => {
+                    stack.push(Frame::AppRhs(term));
+                    break;
                 }
+                Some(Frame::AppRhs(lhs)) => term = app(lhs, term),
             }
-            b"01" => {
-                if let Some((term1, rest1)) = _from_bits(&input[2..]) {
-                    if let Some((term2, rest2)) = _from_bits(rest1) {
-                        Some((app(term1, term2), rest2))
-                    } else {
-                        None
-                    }
-                } else {
-                    None
+        }
+    }
+}
+
+// parses the part of a variable following its first bit
+fn parse_index<B: Bits>(bits: &mut B, variant: Variant) -> Result<usize, Error> {
+    let mut ones = 0;
+    while bits.bit(Token::Index, Context::Variable)? {
+        ones += 1;
+    }
+
+    match variant {
+        Variant::Unary => Ok(ones + 1),
+        Variant::Levenshtein if ones == 0 => Ok(1),
+        Variant::Levenshtein => {
+            // the Levenshtein code of `index - 1`; `ones` is the number of its steps
+            let mut n: usize = 1;
+            for _ in 1..ones {
+                if n >= usize::BITS as usize {
+                    return Err(IndexOverflow {
+                        position: bits.position(),
+                    });
                 }
+                let mut m: usize = 1;
+                for _ in 0..n {
+                    m = (m << 1) | bits.bit(Token::Index, Context::Variable)? as usize;
+                }
+                n = m;
             }
-            b"10" | b"11" => {
-                let i = input.iter().take_while(|&b| *b == b'1').count();
-                if input[2..].is_empty() {
-                    Some((Var(i), b""))
-                } else {
-                    Some((Var(i), &input[i + 1..]))
+
+            n.checked_add(1).ok_or(IndexOverflow {
+                position: bits.position(),
+            })
+        }
+    }
+}
+
+// a reader of "bits", one ASCII character per bit
+struct Reader<'a> {
+    input: &'a [u8],
+    position: usize,
+}
+
+impl Bits for Reader<'_> {
+    // reads the next bit, skipping whitespace
+    fn bit(&mut self, expected: Token, context: Context) -> Result<bool, Error> {
+        loop {
+            let Some(&found) = self.input.get(self.position) else {
+                return Err(UnexpectedEof {
+                    position: self.position,
+                    expected,
+                    context,
+                });
+            };
+            self.position += 1;
+
+            match found {
+                b'0' => return Ok(false),
+                b'1' => return Ok(true),
+                _ if WHITESPACE.contains(&found) => {}
+                _ => {
+                    return Err(InvalidCharacter {
+                        position: self.position - 1,
+                        expected,
+                        found,
+                    });
                 }
             }
-            _ => None,
         }
     }
+
+    fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl<R: Read> Bits for BitReader<R> {
+    fn bit(&mut self, expected: Token, context: Context) -> Result<bool, Error> {
+        let position = self.position();
+
+        match self.read_bit() {
+            Ok(Some(bit)) => Ok(bit),
+            Ok(None) => Err(UnexpectedEof {
+                position,
+                expected,
+                context,
+            }),
+            Err(e) => Err(Io {
+                position,
+                kind: e.kind(),
+            }),
+        }
+    }
+
+    fn position(&self) -> usize {
+        BitReader::position(self)
+    }
+}
+
+/// Read a packed blc-encoded lambda `Term` from a `BitReader`. The reader is left right after the
+/// term's last bit.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::read_term;
+/// use blc::encoding::bits::BitReader;
+/// use lambda_calculus::*;
+///
+/// let mut reader = BitReader::new(&[0b0000_1100, 0b0100_0000][..]);
+///
+/// assert_eq!(read_term(&mut reader), Ok(abs!(2, Var(2))));
+/// assert_eq!(read_term(&mut reader), Ok(abs(Var(1))));
+/// ```
+pub fn read_term<R: Read>(reader: &mut BitReader<R>) -> Result<Term, Error> {
+    parse_from(reader, Variant::Unary)
+}
+
+/// Write a lambda `Term` to a `BitWriter` in packed blc.
+pub fn write_term<W: Write>(term: &Term, writer: &mut BitWriter<W>) -> io::Result<()> {
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        match term {
+            Var(i) => {
+                for _ in 0..*i {
+                    writer.write_bit(true)?;
+                }
+                writer.write_bit(false)?;
+            }
+            Abs(t) => {
+                writer.write_bit(false)?;
+                writer.write_bit(false)?;
+                stack.push(t);
+            }
+            App(boxed) => {
+                writer.write_bit(false)?;
+                writer.write_bit(true)?;
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Parse a packed blc-encoded lambda `Term` (e.g. the contents of a `.Blc` file) like `from_bits`
+/// parses an unpacked one; the padding after the term is ignored.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{from_bits, from_bytes};
+///
+/// assert_eq!(from_bytes(&[0x01, 0xcb, 0xda]), from_bits(b"000000011100101111011010"));
+/// ```
+pub fn from_bytes(input: &[u8]) -> Result<Term, Error> {
+    read_term(&mut BitReader::new(input))
+}
+
+/// Represent a lambda `Term` in packed blc, like `to_bits` does in unpacked one.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{from_bits, to_bytes};
+///
+/// let succ = from_bits(b"000000011100101111011010").unwrap();
+///
+/// assert_eq!(to_bytes(&succ), vec![0x01, 0xcb, 0xda]);
+/// ```
+pub fn to_bytes(term: &Term) -> Vec<u8> {
+    let mut writer = BitWriter::new(Vec::new());
+    write_term(term, &mut writer).unwrap(); // safe; writing to a Vec doesn't fail
+
+    writer.finish().unwrap() // safe
 }
 
 /// Represent a lambda `Term` in blc.
 ///
+/// A variable with the invalid index 0 is represented as `0`, which isn't valid blc; terms
+/// containing one can be rejected with `to_bits_with` or by validating them first (see
+/// `program::Program`).
+///
 /// # Example
 /// ```
 /// use blc::encoding::binary::{from_bits, to_bits};
@@ -82,29 +567,143 @@ fn _from_bits(input: &[u8]) -> Option<(Term, &[u8])> {
 /// ```
 pub fn to_bits(term: &Term) -> Vec<u8> {
     let mut output = Vec::new();
-    _to_bits(term, &mut output);
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        match term {
+            Var(i) => {
+                output.resize(output.len() + i, b'1');
+                output.push(b'0');
+            }
+            Abs(t) => {
+                output.extend_from_slice(b"00");
+                stack.push(t);
+            }
+            App(boxed) => {
+                output.extend_from_slice(b"01");
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
+        }
+    }
 
     output
 }
 
-fn _to_bits(term: &Term, output: &mut Vec<u8>) {
-    match term {
-        Var(i) => {
-            for _ in 0..*i {
-                output.push(b'1')
+/// Represent a lambda `Term` with the given variant of the binary encoding; unlike `to_bits`, it
+/// fails on variables with the invalid index 0.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{to_bits_with, Error, Variant};
+/// use lambda_calculus::*;
+///
+/// assert_eq!(to_bits_with(&abs(Var(3)), Variant::Unary), Ok(b"001110".to_vec()));
+/// assert_eq!(to_bits_with(&abs(Var(3)), Variant::Levenshtein), Ok(b"0011100".to_vec()));
+/// assert_eq!(
+///     to_bits_with(&abs(Var(0)), Variant::Levenshtein),
+///     Err(Error::ZeroIndex { position: 2 })
+/// );
+/// ```
+pub fn to_bits_with(term: &Term, variant: Variant) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        match term {
+            Var(0) => {
+                return Err(ZeroIndex {
+                    position: output.len(),
+                });
+            }
+            Var(i) => match variant {
+                Variant::Unary => {
+                    output.resize(output.len() + i, b'1');
+                    output.push(b'0');
+                }
+                Variant::Levenshtein => {
+                    output.push(b'1');
+                    output.extend(levenshtein(i - 1));
+                }
+            },
+            Abs(t) => {
+                output.extend_from_slice(b"00");
+                stack.push(t);
+            }
+            App(boxed) => {
+                output.extend_from_slice(b"01");
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
             }
-            output.push(b'0');
         }
-        Abs(t) => {
-            output.extend_from_slice(b"00");
-            output.append(&mut to_bits(t));
+    }
+
+    Ok(output)
+}
+
+/// Returns the size of a lambda `Term` in bits in the given variant of the binary encoding; fails
+/// like `to_bits_with`.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{size, Variant};
+/// use lambda_calculus::*;
+///
+/// let deep = abs!(100, Var(100));
+///
+/// assert_eq!(size(&deep, Variant::Unary), Ok(301));
+/// assert_eq!(size(&deep, Variant::Levenshtein), Ok(215));
+/// ```
+pub fn size(term: &Term, variant: Variant) -> Result<usize, Error> {
+    let mut size = 0;
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        match term {
+            Var(0) => return Err(ZeroIndex { position: size }),
+            Var(i) => match variant {
+                Variant::Unary => size += i + 1,
+                Variant::Levenshtein => size += 1 + levenshtein(i - 1).len(),
+            },
+            Abs(t) => {
+                size += 2;
+                stack.push(t);
+            }
+            App(boxed) => {
+                size += 2;
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
         }
-        App(boxed) => {
-            output.extend_from_slice(b"01");
-            output.append(&mut to_bits(&boxed.0));
-            output.append(&mut to_bits(&boxed.1));
+    }
+
+    Ok(size)
+}
+
+// the Levenshtein code of a number, as "bits"
+fn levenshtein(n: usize) -> Vec<u8> {
+    if n == 0 {
+        return b"0".to_vec();
+    }
+
+    let mut code = Vec::new();
+    let mut steps = 1;
+    let mut m = n;
+    loop {
+        // the binary representation of `m` without its leading 1, prepended to the code
+        let length = (usize::BITS - 1 - m.leading_zeros()) as usize;
+        let bits = (0..length).rev().map(|i| b'0' + ((m >> i) & 1) as u8);
+        code.splice(0..0, bits);
+
+        if length == 0 {
+            break;
         }
+        steps += 1;
+        m = length;
     }
+    code.splice(0..0, (0..steps).map(|_| b'1').chain([b'0']));
+
+    code
 }
 
 /// Convert a stream of "bits" into bytes. It is not always reversible with `decompress`, because
@@ -122,7 +721,7 @@ pub fn compress(bits: &[u8]) -> Vec<u8> {
     let mut output = Vec::with_capacity(length / 8 + 1);
     let mut pos = 0;
 
-    while pos <= length - 8 {
+    while pos + 8 <= length {
         output.push(bits_to_byte(&bits[pos..(pos + 8)]));
         pos += 8;
     }
@@ -206,6 +805,94 @@ mod test {
         assert_eq!(from_bits(b"00 00\t00\n10\r\n"), Ok(abs!(3, Var(1))));
     }
 
+    #[test]
+    fn errors() {
+        assert_eq!(
+            from_bits(b""),
+            Err(UnexpectedEof {
+                position: 0,
+                expected: Token::Term,
+                context: Context::Term
+            })
+        );
+        assert_eq!(
+            from_bits(b"0"),
+            Err(UnexpectedEof {
+                position: 1,
+                expected: Token::Kind,
+                context: Context::Term
+            })
+        );
+        assert_eq!(
+            from_bits(b"01 0010"),
+            Err(UnexpectedEof {
+                position: 7,
+                expected: Token::Term,
+                context: Context::Application
+            })
+        );
+        assert_eq!(
+            from_bits(b"00 1x"),
+            Err(InvalidCharacter {
+                position: 4,
+                expected: Token::Index,
+                found: b'x'
+            })
+        );
+        assert_eq!(
+            from_bits(b"x").unwrap_err().to_string(),
+            "invalid character 'x' at bit 0, expected a term"
+        );
+    }
+
+    #[test]
+    fn no_panics() {
+        // all the inputs of up to 7 characters from a representative alphabet
+        let alphabet = b"01 x";
+        let mut inputs = vec![Vec::new()];
+        for _ in 0..7 {
+            inputs = inputs
+                .into_iter()
+                .flat_map(|input| alphabet.iter().map(move |&c| [&input[..], &[c]].concat()))
+                .collect();
+            for input in &inputs {
+                let _ = from_bits(input);
+            }
+        }
+    }
+
+    #[test]
+    fn prefixes() {
+        assert_eq!(from_bits_prefix(b"10"), Ok((Var(1), &b""[..])));
+        assert_eq!(from_bits_prefix(b"110 10"), Ok((Var(2), &b" 10"[..])));
+        let quine_and_input = [&QUINE[..], b"input"].concat();
+        assert_eq!(
+            from_bits_prefix(&quine_and_input),
+            Ok((from_bits(QUINE).unwrap(), &b"input"[..]))
+        );
+        assert!(from_bits_strict(&quine_and_input).is_err());
+        assert_eq!(from_bits_strict(BLC), from_bits(BLC));
+    }
+
+    #[test]
+    fn concatenated_terms() {
+        let mut parsed = terms(b"10\n0010\n01 10x");
+
+        assert_eq!(parsed.next(), Some(Ok(Var(1))));
+        assert_eq!(parsed.next(), Some(Ok(abs(Var(1)))));
+        assert_eq!(parsed.rest(), b"\n01 10x");
+        assert_eq!(
+            parsed.next(),
+            Some(Err(InvalidCharacter {
+                position: 13,
+                expected: Token::Term,
+                found: b'x'
+            }))
+        );
+        assert_eq!(parsed.next(), None);
+        assert_eq!(terms(b" \n").count(), 0);
+    }
+
     #[test]
     fn from_bits_and_back() {
         let k = b"0000110";
@@ -233,6 +920,119 @@ mod test {
         assert_eq!(blc_c.last().unwrap(), &0x1a);
     }
 
+    // drops a term without recursion, which would overflow the stack for deep terms
+    fn dismantle(term: Term) {
+        let mut stack = vec![term];
+        while let Some(term) = stack.pop() {
+            match term {
+                Var(_) => {}
+                Abs(t) => stack.push(*t),
+                App(boxed) => {
+                    let (lhs, rhs) = *boxed;
+                    stack.push(lhs);
+                    stack.push(rhs);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn deep_terms() {
+        const DEPTH: usize = 1 << 20;
+
+        // λλλ…1
+        let mut abstractions = b"00".repeat(DEPTH);
+        abstractions.extend_from_slice(b"10");
+        let term = from_bits(&abstractions).unwrap();
+        assert_eq!(to_bits(&term), abstractions);
+        let unpacked = from_bytes(&to_bytes(&term)).unwrap();
+        assert_eq!(to_bits(&unpacked), abstractions);
+        dismantle(term);
+        dismantle(unpacked);
+
+        // 1 (1 (1 … 1))
+        let mut applications = b"0110".repeat(DEPTH);
+        applications.extend_from_slice(b"10");
+        let term = from_bits(&applications).unwrap();
+        assert_eq!(to_bits(&term), applications);
+        dismantle(term);
+    }
+
+    #[test]
+    fn levenshtein_codes() {
+        assert_eq!(levenshtein(0), b"0");
+        assert_eq!(levenshtein(1), b"10");
+        assert_eq!(levenshtein(2), b"1100");
+        assert_eq!(levenshtein(3), b"1101");
+        assert_eq!(levenshtein(4), b"1110000");
+        assert_eq!(levenshtein(16), b"111100000000");
+    }
+
+    #[test]
+    fn levenshtein_variant() {
+        for i in 1..1000 {
+            let term = abs!(i, app(Var(i), Var(1)));
+            let bits = to_bits_with(&term, Variant::Levenshtein).unwrap();
+
+            assert_eq!(Ok(bits.len()), size(&term, Variant::Levenshtein));
+            assert_eq!(from_bits_with(&bits, Variant::Levenshtein), Ok(term));
+        }
+
+        for bits in [&QUINE[..], &PRIMES[..], &BLC[..]] {
+            let term = from_bits(bits).unwrap();
+            let levenshtein = to_bits_with(&term, Variant::Levenshtein).unwrap();
+
+            assert_eq!(from_bits_with(&levenshtein, Variant::Levenshtein), Ok(term));
+        }
+    }
+
+    #[test]
+    fn zero_index() {
+        let term = abs(app(Var(0), Var(1)));
+
+        assert_eq!(to_bits(&term), b"0001010");
+        for variant in [Variant::Unary, Variant::Levenshtein] {
+            assert_eq!(to_bits_with(&term, variant), Err(ZeroIndex { position: 4 }));
+            assert_eq!(size(&term, variant), Err(ZeroIndex { position: 4 }));
+        }
+    }
+
+    #[test]
+    fn index_overflow() {
+        let mut bits = b"1".repeat(10);
+        bits.extend(b"0".repeat(1000));
+
+        assert!(matches!(
+            from_bits_with(&bits, Variant::Levenshtein),
+            Err(IndexOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn compressing_short_inputs() {
+        assert_eq!(compress(b""), b"");
+        assert_eq!(compress(b"101"), [0xa0]);
+    }
+
+    #[test]
+    fn packed() {
+        for bits in [&PRIMES[..], &BLC[..]] {
+            let term = from_bits(bits).unwrap();
+
+            assert_eq!(to_bytes(&term), compress(&to_bits(&term)));
+            assert_eq!(from_bytes(&to_bytes(&term)), Ok(term));
+        }
+
+        assert_eq!(
+            from_bytes(&[0b0100_0000]),
+            Err(UnexpectedEof {
+                position: 8,
+                expected: Token::Term,
+                context: Context::Abstraction
+            })
+        );
+    }
+
     #[test]
     fn decompression() {
         let s_c = vec![0x1, 0x7a, 0x74];
@@ -0,0 +1,188 @@
+//! Packed bit I/O
+//!
+//! `.Blc` files store BLC programs packed eight bits to a byte, most significant bit first, with
+//! the last byte padded with zeros. A `BitReader` and a `BitWriter` read and write such bit
+//! streams directly, without expanding them to one ASCII character per bit like `decompress`.
+
+use std::io::{self, Read, Write};
+
+/// A reader of packed bits.
+///
+/// Bytes are read from the underlying reader one at a time, so it should be buffered if reading
+/// from it is expensive.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bits::BitReader;
+///
+/// let mut reader = BitReader::new(&[0b1010_0000][..]);
+///
+/// assert_eq!(reader.read_bit().unwrap(), Some(true));
+/// assert_eq!(reader.read_bit().unwrap(), Some(false));
+/// assert_eq!(reader.position(), 2);
+/// ```
+#[derive(Debug)]
+pub struct BitReader<R> {
+    reader: R,
+    byte: u8,
+    remaining: u8,
+    position: usize,
+}
+
+impl<R: Read> BitReader<R> {
+    /// Creates a bit reader reading from the given reader.
+    pub fn new(reader: R) -> Self {
+        BitReader {
+            reader,
+            byte: 0,
+            remaining: 0,
+            position: 0,
+        }
+    }
+
+    /// Reads the next bit; returns `None` at the end of the input.
+    pub fn read_bit(&mut self) -> io::Result<Option<bool>> {
+        if self.remaining == 0 {
+            let mut buf = [0];
+            loop {
+                match self.reader.read(&mut buf) {
+                    Ok(0) => return Ok(None),
+                    Ok(_) => break,
+                    Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+                    Err(e) => return Err(e),
+                }
+            }
+            self.byte = buf[0];
+            self.remaining = 8;
+        }
+
+        self.remaining -= 1;
+        self.position += 1;
+
+        Ok(Some((self.byte >> self.remaining) & 1 == 1))
+    }
+
+    /// Returns the number of bits read so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Discards the unread bits of the current byte, so that the next read starts at a byte
+    /// boundary.
+    pub fn align(&mut self) {
+        self.position += self.remaining as usize;
+        self.remaining = 0;
+    }
+
+    /// Returns the underlying reader; the unread bits of the current byte are lost.
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+}
+
+/// A writer of packed bits.
+///
+/// The last byte is only written (padded with zeros) by `finish`; dropping the writer before
+/// finishing it loses the bits that don't form a full byte yet.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bits::BitWriter;
+///
+/// let mut writer = BitWriter::new(Vec::new());
+/// for bit in [true, false, true] {
+///     writer.write_bit(bit).unwrap();
+/// }
+///
+/// assert_eq!(writer.finish().unwrap(), vec![0b1010_0000]);
+/// ```
+#[derive(Debug)]
+pub struct BitWriter<W> {
+    writer: W,
+    byte: u8,
+    filled: u8,
+    position: usize,
+}
+
+impl<W: Write> BitWriter<W> {
+    /// Creates a bit writer writing to the given writer.
+    pub fn new(writer: W) -> Self {
+        BitWriter {
+            writer,
+            byte: 0,
+            filled: 0,
+            position: 0,
+        }
+    }
+
+    /// Writes a single bit.
+    pub fn write_bit(&mut self, bit: bool) -> io::Result<()> {
+        self.byte = (self.byte << 1) | bit as u8;
+        self.filled += 1;
+        self.position += 1;
+
+        if self.filled == 8 {
+            self.writer.write_all(&[self.byte])?;
+            self.byte = 0;
+            self.filled = 0;
+        }
+
+        Ok(())
+    }
+
+    /// Returns the number of bits written so far.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Writes the last, zero-padded byte (if there is one), flushes the underlying writer and
+    /// returns it.
+    pub fn finish(mut self) -> io::Result<W> {
+        if self.filled != 0 {
+            let byte = self.byte << (8 - self.filled);
+            self.writer.write_all(&[byte])?;
+        }
+        self.writer.flush()?;
+
+        Ok(self.writer)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn reading_and_writing() {
+        let bytes = [0x00, 0xff, 0x5a, 0x81];
+        let mut reader = BitReader::new(&bytes[..]);
+        let mut writer = BitWriter::new(Vec::new());
+
+        while let Some(bit) = reader.read_bit().unwrap() {
+            writer.write_bit(bit).unwrap();
+        }
+
+        assert_eq!(reader.position(), 32);
+        assert_eq!(writer.finish().unwrap(), bytes);
+    }
+
+    #[test]
+    fn alignment() {
+        let mut reader = BitReader::new(&[0b1000_0000, 0b0100_0000][..]);
+
+        assert_eq!(reader.read_bit().unwrap(), Some(true));
+        reader.align();
+        assert_eq!(reader.position(), 8);
+        assert_eq!(reader.read_bit().unwrap(), Some(false));
+        assert_eq!(reader.read_bit().unwrap(), Some(true));
+    }
+
+    #[test]
+    fn padding() {
+        assert_eq!(BitWriter::new(Vec::new()).finish().unwrap(), b"");
+
+        let mut writer = BitWriter::new(Vec::new());
+        writer.write_bit(true).unwrap();
+        assert_eq!(writer.finish().unwrap(), [0x80]);
+    }
+}
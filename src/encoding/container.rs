@@ -0,0 +1,371 @@
+//! A self-describing container format for BLC programs
+//!
+//! Packed programs (see `compress`) can't be unpacked exactly, because the padding of the last byte
+//! is indistinguishable from the program's bits, and they don't say how they expect to be run. A
+//! container stores a program along with its exact length in bits and the kind of input it
+//! expects:
+//!
+//! | field       | size       | contents                                            |
+//! |-------------|------------|-----------------------------------------------------|
+//! | magic       | 4 bytes    | `BLC\0`                                             |
+//! | version     | 1 byte     | `1`                                                 |
+//! | mode        | 1 byte     | the kind of input (see `Mode`)                      |
+//! | name length | 2 bytes    | the length of the name in bytes (little-endian)     |
+//! | name        | variable   | the UTF-8 encoded name of the program; can be empty |
+//! | bit length  | 8 bytes    | the length of the program in bits (little-endian)   |
+//! | payload     | variable   | the packed program, padded with zeros               |
+
+use self::Error::*;
+use crate::encoding::binary::{self, read_term, write_term};
+use crate::encoding::bits::{BitReader, BitWriter};
+use crate::execution::Input;
+use lambda_calculus::*;
+use std::io::{self, Read, Write};
+use std::{error, fmt};
+
+/// The magic bytes starting every container.
+const MAGIC: [u8; 4] = *b"BLC\0";
+
+/// The version of the container format.
+const VERSION: u8 = 1;
+
+/// An error that can occur when reading a container.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// the data doesn't start with the container's magic bytes
+    NotAContainer,
+    /// a version of the format that isn't supported
+    UnsupportedVersion(u8),
+    /// an unknown mode
+    InvalidMode(u8),
+    /// a name that isn't valid UTF-8
+    InvalidName,
+    /// a payload that isn't a valid BLC term
+    InvalidPayload(binary::Error),
+    /// a payload whose term doesn't have the declared length in bits
+    LengthMismatch {
+        /// the declared length
+        declared: u64,
+        /// the length of the stored term
+        actual: u64,
+    },
+    /// an I/O error, including a premature end of the data
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotAContainer => write!(f, "not a BLC container"),
+            UnsupportedVersion(v) => write!(f, "unsupported container version {}", v),
+            InvalidMode(m) => write!(f, "invalid mode {}", m),
+            InvalidName => write!(f, "the name is not valid UTF-8"),
+            InvalidPayload(e) => write!(f, "invalid payload: {}", e),
+            LengthMismatch { declared, actual } => write!(
+                f,
+                "the program has {} bits instead of the declared {}",
+                actual, declared
+            ),
+            Io(kind) => write!(f, "I/O error: {}", io::Error::from(*kind)),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            InvalidPayload(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Io(error.kind())
+    }
+}
+
+/// The kind of input a program expects; there is one for every variant of `execution::Input`,
+/// named the same way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Mode {
+    /// no input (`Input::Nothing`)
+    Nothing,
+    /// BLC input, i.e. a lambda term (`Input::Bits`)
+    Bits,
+    /// unencoded byte input, lambda-encoded as a list of bytes (`Input::Bytes`)
+    #[default]
+    Bytes,
+    /// unencoded byte input in the Lazy K form (`Input::LazyK`)
+    LazyK,
+}
+
+impl From<&Input<'_>> for Mode {
+    fn from(input: &Input) -> Self {
+        match input {
+            Input::Nothing => Mode::Nothing,
+            Input::Bits(_) => Mode::Bits,
+            Input::Bytes(_) => Mode::Bytes,
+            Input::LazyK(_) => Mode::LazyK,
+        }
+    }
+}
+
+impl Mode {
+    /// Returns the `Input` for executing a program in this mode with the given data; the data is
+    /// ignored in the `Nothing` mode.
+    pub fn input(self, data: &[u8]) -> Input<'_> {
+        match self {
+            Mode::Nothing => Input::Nothing,
+            Mode::Bits => Input::Bits(data),
+            Mode::Bytes => Input::Bytes(data),
+            Mode::LazyK => Input::LazyK(data),
+        }
+    }
+
+    fn to_byte(self) -> u8 {
+        match self {
+            Mode::Nothing => 0,
+            Mode::Bits => 1,
+            Mode::Bytes => 2,
+            Mode::LazyK => 3,
+        }
+    }
+
+    fn from_byte(byte: u8) -> Result<Self, Error> {
+        match byte {
+            0 => Ok(Mode::Nothing),
+            1 => Ok(Mode::Bits),
+            2 => Ok(Mode::Bytes),
+            3 => Ok(Mode::LazyK),
+            _ => Err(InvalidMode(byte)),
+        }
+    }
+}
+
+/// Returns `true` if the data starts like a container (with its magic bytes).
+pub fn is_container(data: &[u8]) -> bool {
+    data.starts_with(&MAGIC)
+}
+
+/// A BLC program along with its metadata.
+///
+/// # Example
+/// ```
+/// use blc::encoding::container::{Container, Mode};
+/// use blc::from_bits;
+///
+/// let reverse = from_bits(b"0001011001000110100000000001011100111110111100001011011110110000010");
+/// let container = Container {
+///     term: reverse.unwrap(),
+///     mode: Mode::Bytes,
+///     name: Some("reverse".into()),
+/// };
+///
+/// assert_eq!(Container::from_bytes(&container.to_bytes().unwrap()), Ok(container));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Container {
+    /// the program
+    pub term: Term,
+    /// the kind of input the program expects
+    pub mode: Mode,
+    /// the name of the program; empty names are not distinguished from no name
+    pub name: Option<String>,
+}
+
+impl Container {
+    /// Creates a container with a program expecting the given kind of input and no name.
+    pub fn new(term: Term, mode: Mode) -> Self {
+        Container {
+            term,
+            mode,
+            name: None,
+        }
+    }
+
+    /// Returns the `Input` for executing the program with the given data.
+    pub fn input<'a>(&self, data: &'a [u8]) -> Input<'a> {
+        self.mode.input(data)
+    }
+
+    /// Writes the container; fails with `io::ErrorKind::InvalidInput` if the name is longer than
+    /// 65535 bytes.
+    pub fn write<W: Write>(&self, mut writer: W) -> io::Result<()> {
+        let name = self.name.as_deref().unwrap_or("").as_bytes();
+        let Ok(name_length) = u16::try_from(name.len()) else {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "the name is longer than 65535 bytes",
+            ));
+        };
+
+        let mut bits = BitWriter::new(Vec::new());
+        write_term(&self.term, &mut bits)?;
+        let length = bits.position() as u64;
+        let payload = bits.finish()?;
+
+        writer.write_all(&MAGIC)?;
+        writer.write_all(&[VERSION, self.mode.to_byte()])?;
+        writer.write_all(&name_length.to_le_bytes())?;
+        writer.write_all(name)?;
+        writer.write_all(&length.to_le_bytes())?;
+        writer.write_all(&payload)?;
+
+        writer.flush()
+    }
+
+    /// Reads a container; the reader is left right after its payload.
+    pub fn read<R: Read>(mut reader: R) -> Result<Self, Error> {
+        let mut magic = [0; 4];
+        reader.read_exact(&mut magic)?;
+        if magic != MAGIC {
+            return Err(NotAContainer);
+        }
+
+        let mut header = [0; 4];
+        reader.read_exact(&mut header)?;
+        let [version, mode, name_length @ ..] = header;
+        if version != VERSION {
+            return Err(UnsupportedVersion(version));
+        }
+        let mode = Mode::from_byte(mode)?;
+
+        let mut name = vec![0; u16::from_le_bytes(name_length) as usize];
+        reader.read_exact(&mut name)?;
+        let name = String::from_utf8(name).or(Err(InvalidName))?;
+
+        let mut length = [0; 8];
+        reader.read_exact(&mut length)?;
+        let declared = u64::from_le_bytes(length);
+
+        let mut bits = BitReader::new(reader.take(declared.div_ceil(8)));
+        let term = read_term(&mut bits).map_err(|e| match e {
+            binary::Error::Io { kind, .. } => Io(kind),
+            e => InvalidPayload(e),
+        })?;
+        let actual = bits.position() as u64;
+        if actual != declared {
+            return Err(LengthMismatch { declared, actual });
+        }
+        io::copy(&mut bits.into_inner(), &mut io::sink())?; // skip the rest of the payload
+
+        Ok(Container {
+            term,
+            mode,
+            name: (!name.is_empty()).then_some(name),
+        })
+    }
+
+    /// Serializes the container into bytes; fails like `write`.
+    pub fn to_bytes(&self) -> io::Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+        self.write(&mut bytes)?;
+
+        Ok(bytes)
+    }
+
+    /// Deserializes a container from bytes; trailing data is ignored.
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, Error> {
+        Container::read(bytes)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::binary::{compress, decompress, from_bits};
+
+    #[test]
+    fn exact_length() {
+        // 7 bits, so the padding could be taken for another variable by `decompress`
+        let k = from_bits(b"0000110").unwrap();
+        let container = Container::new(k.clone(), Mode::Nothing);
+        let bytes = container.to_bytes().unwrap();
+
+        assert_ne!(decompress(&compress(b"0000110")), b"0000110");
+        assert_eq!(bytes.len(), 4 + 4 + 8 + 1);
+        assert_eq!(Container::from_bytes(&bytes), Ok(container));
+    }
+
+    #[test]
+    fn modes() {
+        for mode in [Mode::Nothing, Mode::Bits, Mode::Bytes, Mode::LazyK] {
+            let container = Container::new(abs(Var(1)), mode);
+            let bytes = container.to_bytes().unwrap();
+
+            assert_eq!(Container::from_bytes(&bytes), Ok(container), "{:?}", mode);
+            assert_eq!(Mode::from(&mode.input(b"0010")), mode);
+        }
+    }
+
+    #[test]
+    fn invalid_containers() {
+        let container = Container::new(abs(Var(1)), Mode::Bits).to_bytes().unwrap();
+
+        assert_eq!(
+            Container::from_bytes(b"BLC"),
+            Err(Io(io::ErrorKind::UnexpectedEof))
+        );
+        assert_eq!(Container::from_bytes(b"BLC1...."), Err(NotAContainer));
+
+        let mut modified = container.clone();
+        modified[4] = 2;
+        assert_eq!(Container::from_bytes(&modified), Err(UnsupportedVersion(2)));
+
+        let mut modified = container.clone();
+        modified[5] = 9;
+        assert_eq!(Container::from_bytes(&modified), Err(InvalidMode(9)));
+
+        let mut modified = container.clone();
+        modified[8] = 3;
+        assert_eq!(
+            Container::from_bytes(&modified),
+            Err(LengthMismatch {
+                declared: 3,
+                actual: 4
+            })
+        );
+
+        let truncated = &container[..container.len() - 1];
+        assert_eq!(
+            Container::from_bytes(truncated),
+            Err(InvalidPayload(binary::Error::UnexpectedEof {
+                position: 0,
+                expected: binary::Token::Term,
+                context: binary::Context::Term
+            }))
+        );
+    }
+
+    #[test]
+    fn long_name() {
+        let container = Container {
+            term: abs(Var(1)),
+            mode: Mode::Bytes,
+            name: Some("a".repeat(1 << 16)),
+        };
+
+        assert_eq!(
+            container.to_bytes().map_err(|e| e.kind()),
+            Err(io::ErrorKind::InvalidInput)
+        );
+    }
+
+    #[test]
+    fn reading_consecutive_containers() {
+        let first = Container {
+            term: abs(Var(1)),
+            mode: Mode::LazyK,
+            name: Some("identity".into()),
+        };
+        let second = Container::new(abs!(2, Var(2)), Mode::Bytes);
+        let bytes = [first.to_bytes().unwrap(), second.to_bytes().unwrap()].concat();
+        let mut reader = &bytes[..];
+
+        assert_eq!(Container::read(&mut reader), Ok(first));
+        assert_eq!(Container::read(&mut reader), Ok(second));
+        assert!(reader.is_empty());
+    }
+}
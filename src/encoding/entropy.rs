@@ -0,0 +1,448 @@
+//! Entropy-coded term compression
+//!
+//! Plain BLC spends exactly two bits on every abstraction and application and `i + 1` bits on
+//! every variable `i`, no matter how often they occur. This module instead arithmetic-codes the
+//! term tree with an adaptive model: every constructor is coded as one or two binary decisions
+//! whose probabilities are learned separately for every kind of parent node and every sequence of
+//! the 8 preceding constructors, and every De Bruijn index is coded as its bit length (in unary)
+//! followed by its remaining bits, each of them with its own learned probability.
+//!
+//! Generated terms with a repetitive structure compress much better than with `binary::compress`;
+//! hand-optimized programs have little redundancy left and stay about the same size. Measured in
+//! bytes, on the programs from the tests:
+//!
+//! | term                                 | `binary::compress` | `entropy::compress` |
+//! |--------------------------------------|-------------------:|--------------------:|
+//! | `inflate`                            |                 30 |                  32 |
+//! | `sort`                               |                 55 |                  57 |
+//! | FizzBuzz step                        |                401 |                 251 |
+//! | 4400 characters of `encode`d text    |              71801 |               14047 |
+//!
+//! The compressed data is self-delimiting and can be decompressed losslessly.
+
+use self::Error::*;
+use crate::encoding::bits::{BitReader, BitWriter};
+use lambda_calculus::*;
+use std::{error, fmt};
+
+/// An error that can occur when decompressing a term.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// the data ended before the term was complete
+    UnexpectedEof,
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnexpectedEof => write!(f, "the data ended before the term was complete"),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+/// Compress a lambda `Term`.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::{compress as pack, to_bits};
+/// use blc::encoding::entropy::{compress, decompress};
+/// use blc::encoding::lambda::encode;
+///
+/// let text = encode(&b"All work and no play makes Jack a dull boy. ".repeat(100));
+/// let compressed = compress(&text);
+///
+/// assert!(compressed.len() < pack(&to_bits(&text)).len());
+/// assert_eq!(decompress(&compressed), Ok(text));
+/// ```
+pub fn compress(term: &Term) -> Vec<u8> {
+    let mut model = Model::default();
+    let mut encoder = Encoder::new();
+
+    let mut stack = vec![(term, Parent::Root)];
+    while let Some((term, parent)) = stack.pop() {
+        let context = model.context(parent);
+
+        match term {
+            Var(i) => {
+                encoder.encode(true, &mut model.is_var[context]);
+                model.encode_index(&mut encoder, *i);
+                model.push(Constructor::Var);
+            }
+            Abs(t) => {
+                encoder.encode(false, &mut model.is_var[context]);
+                encoder.encode(false, &mut model.is_app[context]);
+                model.push(Constructor::Abs);
+                stack.push((t, Parent::Abs));
+            }
+            App(boxed) => {
+                encoder.encode(false, &mut model.is_var[context]);
+                encoder.encode(true, &mut model.is_app[context]);
+                model.push(Constructor::App);
+                stack.push((&boxed.1, Parent::AppRhs));
+                stack.push((&boxed.0, Parent::AppLhs));
+            }
+        }
+    }
+
+    encoder.finish()
+}
+
+/// Decompress a lambda `Term` compressed with `compress`; data following it is ignored.
+pub fn decompress(data: &[u8]) -> Result<Term, Error> {
+    enum Frame {
+        Abs,
+        AppLhs,
+        AppRhs(Term),
+    }
+
+    let mut model = Model::default();
+    let mut decoder = Decoder::new(data);
+    let mut stack = Vec::new();
+
+    loop {
+        let context = model.context(match stack.last() {
+            None => Parent::Root,
+            Some(Frame::Abs) => Parent::Abs,
+            Some(Frame::AppLhs) => Parent::AppLhs,
+            Some(Frame::AppRhs(_)) => Parent::AppRhs,
+        });
+
+        let mut term = if decoder.decode(&mut model.is_var[context])? {
+            let index = model.decode_index(&mut decoder)?;
+            model.push(Constructor::Var);
+            Var(index)
+        } else if decoder.decode(&mut model.is_app[context])? {
+            model.push(Constructor::App);
+            stack.push(Frame::AppLhs);
+            continue;
+        } else {
+            model.push(Constructor::Abs);
+            stack.push(Frame::Abs);
+            continue;
+        };
+
+        loop {
+            match stack.pop() {
+                None => return Ok(term),
+                Some(Frame::Abs) => term = abs(term),
+                Some(Frame::AppLhs) => {
+                    stack.push(Frame::AppRhs(term));
+                    break;
+                }
+                Some(Frame::AppRhs(lhs)) => term = app(lhs, term),
+            }
+        }
+    }
+}
+
+// the kind of a term's parent, which determines the context of its constructor
+#[derive(Clone, Copy)]
+enum Parent {
+    Root,
+    Abs,
+    AppLhs,
+    AppRhs,
+}
+
+/// The number of bits of the largest possible index.
+const INDEX_BITS: usize = usize::BITS as usize;
+
+#[derive(Clone, Copy)]
+enum Constructor {
+    Var,
+    Abs,
+    App,
+}
+
+/// The number of preceding constructors the probabilities of a constructor depend on.
+const ORDER: u32 = 8;
+
+/// The number of distinct sequences of preceding constructors.
+const HISTORIES: usize = 3usize.pow(ORDER);
+
+// the adaptive probabilities of all the coded decisions
+struct Model {
+    // the preceding constructors, as a base-3 number
+    history: usize,
+    // for every combination of a parent kind and a history
+    is_var: Vec<Probability>,
+    is_app: Vec<Probability>,
+    // whether an index has more bits than the position; for every position
+    length: [Probability; INDEX_BITS],
+    // the bits of an index below its leading one; for every length and position
+    mantissa: Vec<Probability>,
+}
+
+impl Default for Model {
+    fn default() -> Self {
+        Model {
+            history: 0,
+            is_var: vec![Probability::default(); 4 * HISTORIES],
+            is_app: vec![Probability::default(); 4 * HISTORIES],
+            length: [Probability::default(); INDEX_BITS],
+            mantissa: vec![Probability::default(); INDEX_BITS * INDEX_BITS],
+        }
+    }
+}
+
+impl Model {
+    fn context(&self, parent: Parent) -> usize {
+        parent as usize * HISTORIES + self.history
+    }
+
+    fn push(&mut self, constructor: Constructor) {
+        self.history = (self.history * 3 + constructor as usize) % HISTORIES;
+    }
+
+    fn encode_index(&mut self, encoder: &mut Encoder, index: usize) {
+        let length = (usize::BITS - index.leading_zeros()) as usize;
+
+        // the longest possible length needs no terminator
+        for position in 0..length {
+            encoder.encode(true, &mut self.length[position]);
+        }
+        if length < INDEX_BITS {
+            encoder.encode(false, &mut self.length[length]);
+        }
+        for position in (0..length.saturating_sub(1)).rev() {
+            let bit = (index >> position) & 1 == 1;
+            encoder.encode(
+                bit,
+                &mut self.mantissa[(length - 1) * INDEX_BITS + position],
+            );
+        }
+    }
+
+    fn decode_index(&mut self, decoder: &mut Decoder) -> Result<usize, Error> {
+        let mut length = 0;
+        while length < INDEX_BITS && decoder.decode(&mut self.length[length])? {
+            length += 1;
+        }
+        if length == 0 {
+            return Ok(0);
+        }
+
+        let mut index = 1;
+        for position in (0..length - 1).rev() {
+            let bit = decoder.decode(&mut self.mantissa[(length - 1) * INDEX_BITS + position])?;
+            index = (index << 1) | bit as usize;
+        }
+
+        Ok(index)
+    }
+}
+
+/// The number of bits of a probability.
+const PROBABILITY_BITS: u32 = 12;
+
+/// The rate at which probabilities adapt; the larger, the slower.
+const ADAPTATION_SHIFT: u32 = 5;
+
+// the adaptive probability of a decision being `false`
+#[derive(Clone, Copy)]
+struct Probability(u32);
+
+impl Default for Probability {
+    fn default() -> Self {
+        Probability(1 << (PROBABILITY_BITS - 1))
+    }
+}
+
+impl Probability {
+    fn update(&mut self, bit: bool) {
+        if bit {
+            self.0 -= self.0 >> ADAPTATION_SHIFT;
+        } else {
+            self.0 += ((1 << PROBABILITY_BITS) - self.0) >> ADAPTATION_SHIFT;
+        }
+    }
+}
+
+const HALF: u64 = 1 << 31;
+const QUARTER: u64 = 1 << 30;
+const TOP: u64 = (1 << 32) - 1;
+
+// splits the interval `[low, high]` in proportion to the probability of `false`; returns the end
+// of the `false` part
+fn split(low: u64, high: u64, probability: Probability) -> u64 {
+    low + (((high - low + 1) * probability.0 as u64) >> PROBABILITY_BITS) - 1
+}
+
+// a binary arithmetic encoder
+struct Encoder {
+    low: u64,
+    high: u64,
+    pending: usize,
+    output: BitWriter<Vec<u8>>,
+}
+
+impl Encoder {
+    fn new() -> Self {
+        Encoder {
+            low: 0,
+            high: TOP,
+            pending: 0,
+            output: BitWriter::new(Vec::new()),
+        }
+    }
+
+    fn encode(&mut self, bit: bool, probability: &mut Probability) {
+        let split = split(self.low, self.high, *probability);
+        if bit {
+            self.low = split + 1;
+        } else {
+            self.high = split;
+        }
+        probability.update(bit);
+
+        loop {
+            if self.high < HALF {
+                self.emit(false);
+            } else if self.low >= HALF {
+                self.emit(true);
+                self.low -= HALF;
+                self.high -= HALF;
+            } else if self.low >= QUARTER && self.high < HALF + QUARTER {
+                self.pending += 1;
+                self.low -= QUARTER;
+                self.high -= QUARTER;
+            } else {
+                break;
+            }
+            self.low <<= 1;
+            self.high = (self.high << 1) | 1;
+        }
+    }
+
+    // writes a bit followed by the pending opposite ones
+    fn emit(&mut self, bit: bool) {
+        self.write(bit);
+        for _ in 0..self.pending {
+            self.write(!bit);
+        }
+        self.pending = 0;
+    }
+
+    fn write(&mut self, bit: bool) {
+        self.output.write_bit(bit).unwrap(); // safe; writing to a Vec doesn't fail
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        // two more bits select a point that is inside the final interval
+        self.pending += 1;
+        self.emit(self.low >= QUARTER);
+
+        self.output.finish().unwrap() // safe
+    }
+}
+
+// a binary arithmetic decoder
+struct Decoder<'a> {
+    low: u64,
+    high: u64,
+    value: u64,
+    input: BitReader<&'a [u8]>,
+    // the number of bits read past the end of the input
+    overrun: usize,
+}
+
+impl<'a> Decoder<'a> {
+    fn new(input: &'a [u8]) -> Self {
+        let mut decoder = Decoder {
+            low: 0,
+            high: TOP,
+            value: 0,
+            input: BitReader::new(input),
+            overrun: 0,
+        };
+        for _ in 0..32 {
+            decoder.value = (decoder.value << 1) | decoder.read() as u64;
+        }
+
+        decoder
+    }
+
+    fn decode(&mut self, probability: &mut Probability) -> Result<bool, Error> {
+        // the encoder's output is complete after 32 bits past its end at the latest
+        if self.overrun > 32 {
+            return Err(UnexpectedEof);
+        }
+
+        let split = split(self.low, self.high, *probability);
+        let bit = self.value > split;
+        if bit {
+            self.low = split + 1;
+        } else {
+            self.high = split;
+        }
+        probability.update(bit);
+
+        loop {
+            if self.high < HALF {
+            } else if self.low >= HALF {
+                self.low -= HALF;
+                self.high -= HALF;
+                self.value -= HALF;
+            } else if self.low >= QUARTER && self.high < HALF + QUARTER {
+                self.low -= QUARTER;
+                self.high -= QUARTER;
+                self.value -= QUARTER;
+            } else {
+                break;
+            }
+            self.low <<= 1;
+            self.high = (self.high << 1) | 1;
+            self.value = (self.value << 1) | self.read() as u64;
+        }
+
+        Ok(bit)
+    }
+
+    // reads the next bit of the input; past its end, the input is padded with zeros
+    fn read(&mut self) -> bool {
+        match self.input.read_bit() {
+            Ok(Some(bit)) => bit,
+            _ => {
+                self.overrun += 1;
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::binary::from_bits;
+    use lambda_calculus::combinators::{S, Y};
+
+    #[test]
+    fn round_trips() {
+        let terms = [
+            Var(1),
+            Var(0),
+            Var(usize::MAX),
+            abs!(1000, Var(1000)),
+            app!(S(), Y(), abs(Var(7))),
+            from_bits(b"000101100100011010000000000001011011110010111100111111011111011010")
+                .unwrap(),
+        ];
+
+        for term in terms {
+            assert_eq!(decompress(&compress(&term)), Ok(term));
+        }
+    }
+
+    #[test]
+    fn truncated_data() {
+        let compressed = compress(&abs!(1000, Var(1000)));
+
+        assert_eq!(
+            decompress(&compressed[..compressed.len() / 2]),
+            Err(UnexpectedEof)
+        );
+        assert_eq!(decompress(&[]), Err(UnexpectedEof));
+    }
+}
@@ -1,10 +1,53 @@
-//! Lambda encoding for strings of bytes
+//! Lambda encoding for strings of bytes and other data
+//!
+//! Apart from byte strings, any type implementing `LambdaEncode` can be passed to a program and
+//! any type implementing `LambdaDecode` can be obtained from its output (see
+//! `execution::run_typed`). The implementations provided here use the following encodings:
+//!
+//! | type                     | lambda encoding                                    |
+//! |--------------------------|----------------------------------------------------|
+//! | `bool`                   | Church booleans: `λλ2` (`true`), `λλ1` (`false`)   |
+//! | `u8`..`u64`, `usize`     | Church numerals: `λλ2(2(..(21)))`                  |
+//! | `Vec<T>`                 | single-pair lists: `λ1 head tail`, ended by `λλ1`  |
+//! | `Option<T>`              | `λλ2` (`None`), `λλ1 x` (`Some(x)`)                |
+//! | `(A, B)`, `(A, B, C)`, … | Church tuples: `λ1 a b ..`                         |
+//! | `String`                 | like byte input (see `encode`), as UTF-8           |
+//! | `Box<T>`                 | like `T`                                           |
+//! | `Term`                   | as is                                              |
+//!
+//! With the `derive` feature, both traits can also be derived for structs and enums; structs are
+//! encoded as Church tuples (see `tuple`) and enums as Scott-encoded sums (see `sum`) by default.
 
-use crate::encoding::binary::Error;
+use self::Error::*;
+use crate::execution::arithmetic::church;
 use crate::pair_list::*;
 use lambda_calculus::data::boolean::{fls, tru};
 use lambda_calculus::term::*;
-use std::char;
+use lambda_calculus::{abs, app};
+use std::{char, error, fmt};
+
+#[cfg(feature = "derive")]
+pub use blc_derive::{LambdaDecode, LambdaEncode};
+
+/// An error that can occur if a lambda term doesn't encode the expected data.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// not a lambda-encoded byte; contains the offending term
+    NotAByte(Term),
+    /// not a lambda encoding of the expected type; contains its name and the offending term
+    Unexpected(&'static str, Term),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotAByte(term) => write!(f, "not a lambda-encoded byte: {:?}", term),
+            Unexpected(name, term) => write!(f, "not a lambda-encoded {}: {:?}", name, term),
+        }
+    }
+}
+
+impl error::Error for Error {}
 
 /// Decode lambda-encoded data as a `String`.
 ///
@@ -17,41 +60,73 @@ use std::char;
 ///
 /// assert_eq!(decode(k).unwrap(), "(λλ2)");
 /// ```
-pub fn decode(term: Term) -> Result<String, Error> {
-    if term == fls() {
-        Ok("".into())
-    } else if is_list(&term) && is_list(head_ref(&term).unwrap()) {
-        // safe
+pub fn decode(mut term: Term) -> Result<String, Error> {
+    // checking the end of the list once is enough; all its tails end the same way
+    let terminated = is_list(&term);
+    let mut output = String::new();
+
+    loop {
+        if term == fls() {
+            return Ok(output);
+        }
+
+        let chr = match head_ref(&term) {
+            Ok(head) if terminated && is_list(head) => None,
+            // unlike bytes, bits are decoded even if the list isn't terminated
+            Ok(head) if *head == fls() => Some('1'),
+            Ok(head) if *head == tru() => Some('0'),
+            _ => {
+                output.push_str(&format!("({:?})", term));
+                return Ok(output);
+            }
+        };
+
         let (head, tail) = uncons(term).unwrap(); // safe
-        let byte = decode_byte(head)?;
-        let chr = char::from(byte);
-        Ok(chr.to_string() + &decode(tail)?)
-    } else if head_ref(&term) == Ok(&fls()) {
-        Ok("1".to_string() + &decode(tail(term).unwrap())?) // safe
-    } else if head_ref(&term) == Ok(&tru()) {
-        Ok("0".to_string() + &decode(tail(term).unwrap())?) // safe
-    } else {
-        Ok(format!("({:?})", term))
+        match chr {
+            Some(chr) => output.push(chr),
+            None => output.push(char::from(decode_byte(head)?)),
+        }
+        term = tail;
     }
 }
 
-fn decode_byte(encoded_byte: Term) -> Result<u8, Error> {
-    let indices = vectorize_list(encoded_byte)
+/// Decode a single lambda-encoded byte.
+///
+/// # Example
+/// ```
+/// use blc::encoding::lambda::{decode_byte, encode_byte};
+///
+/// assert_eq!(decode_byte(encode_byte(b'a')), Ok(b'a'));
+/// ```
+pub fn decode_byte(encoded_byte: Term) -> Result<u8, Error> {
+    let indices = vectorize_list(encoded_byte.clone())
         .into_iter()
         .map(|t| t.unabs().and_then(|t| t.unabs()).and_then(|t| t.unvar()))
         .collect::<Result<Vec<usize>, TermError>>();
 
-    if let Ok(indices) = indices {
-        Ok(!indices
-            .into_iter()
-            .map(|b| (b - 1) as u8)
-            .fold(0, |acc, b| acc * 2 + b))
-    } else {
-        Err(Error::NotATerm)
+    match indices {
+        Ok(indices) if indices.len() == 8 && indices.iter().all(|&i| i == 1 || i == 2) => {
+            Ok(!indices
+                .into_iter()
+                .map(|b| (b - 1) as u8)
+                .fold(0, |acc, b| acc * 2 + b))
+        }
+        _ => Err(NotAByte(encoded_byte)),
     }
 }
 
-fn encode_byte(byte: u8) -> Term {
+/// Encode a single byte as a lambda `Term`.
+///
+/// # Example
+/// ```
+/// use blc::encoding::lambda::encode_byte;
+///
+/// assert_eq!(
+///     &*format!("{:?}", encode_byte(b'a')),
+///     "λ1(λλ2)(λ1(λλ1)(λ1(λλ1)(λ1(λλ2)(λ1(λλ2)(λ1(λλ2)(λ1(λλ2)(λ1(λλ1)(λλ1))))))))"
+/// );
+/// ```
+pub fn encode_byte(byte: u8) -> Term {
     let bitstr = format!("{:08b}", byte);
     let bits = bitstr.as_bytes();
     listify_terms(
@@ -80,14 +155,388 @@ fn encode_bit(bit: u8) -> Term {
 ///     "λ1(λ1(λλ2)(λ1(λλ1)(λ1(λλ1)(λ1(λλ2)(λ1(λλ2)(λ1(λλ2)(λ1(λλ2)(λ1(λλ1)(λλ1)))))))))(λλ1)"
 /// );
 /// ```
+#[cfg_attr(
+    feature = "tracing",
+    tracing::instrument(level = "debug", skip_all, fields(size = input.len()))
+)]
 pub fn encode(input: &[u8]) -> Term {
     listify_terms(input.iter().map(|&b| encode_byte(b)).collect::<Vec<Term>>())
 }
 
+/// Encode a sequence of byte strings (e.g. command-line arguments) as a lambda `Term`: a
+/// single-pair list of byte strings, each of them encoded with `encode`.
+///
+/// # Example
+/// ```
+/// use blc::encoding::lambda::{encode, encode_args};
+/// use lambda_calculus::*;
+///
+/// assert_eq!(encode_args(&[]), abs!(2, Var(1)));
+/// assert_eq!(encode_args(&[b"a"]), abs(app!(Var(1), encode(b"a"), abs!(2, Var(1)))));
+/// ```
+pub fn encode_args(args: &[&[u8]]) -> Term {
+    listify_terms(args.iter().map(|arg| encode(arg)).collect::<Vec<Term>>())
+}
+
+/// A type that can be lambda-encoded.
+pub trait LambdaEncode {
+    /// Encodes the value as a closed lambda `Term`.
+    fn lambda_encode(&self) -> Term;
+}
+
+/// A type that can be decoded from a lambda term in normal form.
+pub trait LambdaDecode: Sized {
+    /// Decodes a value from a lambda `Term`.
+    fn lambda_decode(term: Term) -> Result<Self, Error>;
+}
+
+impl LambdaEncode for bool {
+    fn lambda_encode(&self) -> Term {
+        if *self { tru() } else { fls() }
+    }
+}
+
+impl LambdaDecode for bool {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        if term == tru() {
+            Ok(true)
+        } else if term == fls() {
+            Ok(false)
+        } else {
+            Err(Unexpected("bool", term))
+        }
+    }
+}
+
+macro_rules! impl_numeral {
+    ($($t:ty),*) => {$(
+        impl LambdaEncode for $t {
+            fn lambda_encode(&self) -> Term {
+                let mut numeral = Var(1);
+                for _ in 0..*self {
+                    numeral = app(Var(2), numeral);
+                }
+                abs(abs(numeral))
+            }
+        }
+
+        impl LambdaDecode for $t {
+            fn lambda_decode(term: Term) -> Result<Self, Error> {
+                match church(&term).and_then(|n| n.try_into().ok()) {
+                    Some(n) => Ok(n),
+                    None => Err(Unexpected(stringify!($t), term)),
+                }
+            }
+        }
+    )*};
+}
+
+impl_numeral!(u8, u16, u32, u64, usize);
+
+impl<T: LambdaEncode> LambdaEncode for Vec<T> {
+    fn lambda_encode(&self) -> Term {
+        listify_terms(self.iter().map(T::lambda_encode).collect())
+    }
+}
+
+impl<T: LambdaDecode> LambdaDecode for Vec<T> {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        if term != fls() && !is_list(&term) {
+            return Err(Unexpected("list", term));
+        }
+
+        vectorize_list(term)
+            .into_iter()
+            .map(T::lambda_decode)
+            .collect()
+    }
+}
+
+impl<T: LambdaEncode> LambdaEncode for Option<T> {
+    fn lambda_encode(&self) -> Term {
+        match self {
+            None => tru(),
+            Some(value) => sum(2, 1, vec![value.lambda_encode()]),
+        }
+    }
+}
+
+impl<T: LambdaDecode> LambdaDecode for Option<T> {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        match split(term, &[0, 1], "option")? {
+            (0, _) => Ok(None),
+            (_, mut value) => T::lambda_decode(value.pop().unwrap()).map(Some), // safe
+        }
+    }
+}
+
+/// Encode the variant with the given index of a sum type with the given number of variants,
+/// along with its (lambda-encoded) fields, as a Scott-encoded sum: one abstraction for each
+/// variant, with the body applying the variant's abstraction to the fields.
+///
+/// # Example
+/// ```
+/// use blc::encoding::lambda::sum;
+/// use lambda_calculus::*;
+///
+/// assert_eq!(sum(2, 0, vec![]), abs!(2, Var(2)));
+/// assert_eq!(sum(3, 2, vec![Var(4), Var(5)]), abs!(3, app!(Var(1), Var(4), Var(5))));
+/// ```
+pub fn sum(variants: usize, index: usize, fields: Vec<Term>) -> Term {
+    assert!(index < variants, "variant index out of range");
+
+    let body = fields.into_iter().fold(Var(variants - index), app);
+    (0..variants).fold(body, |term, _| abs(term))
+}
+
+/// Decode a Scott-encoded sum (see `sum`) whose variants have the given numbers of fields,
+/// returning the index of the variant and the fields.
+///
+/// # Example
+/// ```
+/// use blc::encoding::lambda::{sum, unsum};
+///
+/// let encoded = sum(3, 1, vec![true.into()]);
+///
+/// assert_eq!(unsum(encoded.clone(), &[0, 1, 2]), Ok((1, vec![true.into()])));
+/// assert!(unsum(encoded, &[0, 0, 0]).is_err());
+/// ```
+pub fn unsum(term: Term, arities: &[usize]) -> Result<(usize, Vec<Term>), Error> {
+    split(term, arities, "sum")
+}
+
+/// Encode (lambda-encoded) fields as a Church tuple: `λ1 a b ..`; it is the only variant of a
+/// Scott-encoded sum.
+pub fn tuple(fields: Vec<Term>) -> Term {
+    sum(1, 0, fields)
+}
+
+/// Decode a Church tuple with the given number of fields.
+pub fn untuple(term: Term, arity: usize) -> Result<Vec<Term>, Error> {
+    split(term, &[arity], "tuple").map(|(_, fields)| fields)
+}
+
+/// Encode the variant with the given index of a sum type, along with its (lambda-encoded)
+/// fields, as a Church pair of the index (a Church numeral) and a Church tuple of the fields.
+///
+/// # Example
+/// ```
+/// use blc::encoding::lambda::{tag, tuple, LambdaEncode};
+///
+/// assert_eq!(tag(2, vec![]), tuple(vec![2u8.lambda_encode(), tuple(vec![])]));
+/// ```
+pub fn tag(index: usize, fields: Vec<Term>) -> Term {
+    tuple(vec![index.lambda_encode(), tuple(fields)])
+}
+
+/// Decode a tagged variant (see `tag`) of a sum type whose variants have the given numbers of
+/// fields, returning the index of the variant and the fields.
+pub fn untag(term: Term, arities: &[usize]) -> Result<(usize, Vec<Term>), Error> {
+    let valid = split_ref(&term, &[2]).is_some_and(|(_, pair)| {
+        church(pair[0])
+            .filter(|&index| index < arities.len())
+            .is_some_and(|index| split_ref(pair[1], &[arities[index]]).is_some())
+    });
+    if !valid {
+        return Err(Unexpected("tagged sum", term));
+    }
+
+    let mut pair = untuple(term, 2)?.into_iter();
+    let (index, fields) = (pair.next().unwrap(), pair.next().unwrap()); // safe
+    let index = church(&index).unwrap(); // safe
+
+    Ok((index, untuple(fields, arities[index])?))
+}
+
+// finds the variant of a Scott-encoded sum and its fields, if they are closed and there is the
+// expected number of them
+fn split_ref<'a>(term: &'a Term, arities: &[usize]) -> Option<(usize, Vec<&'a Term>)> {
+    let mut body = term;
+    for _ in arities {
+        body = body.unabs_ref().ok()?;
+    }
+
+    let mut fields = Vec::new();
+    while let App(boxed) = body {
+        fields.push(&boxed.1);
+        body = &boxed.0;
+    }
+    fields.reverse();
+
+    let index = arities.len().checked_sub(*body.unvar_ref().ok()?)?;
+    if arities.get(index) == Some(&fields.len()) && fields.iter().all(|f| !f.has_free_variables()) {
+        Some((index, fields))
+    } else {
+        None
+    }
+}
+
+fn split(term: Term, arities: &[usize], name: &'static str) -> Result<(usize, Vec<Term>), Error> {
+    let Some((index, _)) = split_ref(&term, arities) else {
+        return Err(Unexpected(name, term));
+    };
+
+    let mut body = term;
+    for _ in arities {
+        body = body.unabs().unwrap(); // safe
+    }
+    let mut fields = Vec::with_capacity(arities[index]);
+    for _ in 0..arities[index] {
+        let (lhs, rhs) = body.unapp().unwrap(); // safe
+        fields.push(rhs);
+        body = lhs;
+    }
+    fields.reverse();
+
+    Ok((index, fields))
+}
+
+macro_rules! impl_tuple {
+    ($arity:expr; $($t:ident),*) => {
+        impl<$($t: LambdaEncode),*> LambdaEncode for ($($t,)*) {
+            #[allow(non_snake_case)]
+            fn lambda_encode(&self) -> Term {
+                let ($($t,)*) = self;
+                tuple(vec![$($t.lambda_encode()),*])
+            }
+        }
+
+        impl<$($t: LambdaDecode),*> LambdaDecode for ($($t,)*) {
+            fn lambda_decode(term: Term) -> Result<Self, Error> {
+                let mut elements = untuple(term, $arity)?.into_iter();
+                // safe; there are exactly as many elements as types
+                Ok(($($t::lambda_decode(elements.next().unwrap())?,)*))
+            }
+        }
+    };
+}
+
+impl_tuple!(2; A, B);
+impl_tuple!(3; A, B, C);
+impl_tuple!(4; A, B, C, D);
+impl_tuple!(5; A, B, C, D, E);
+
+impl LambdaEncode for String {
+    fn lambda_encode(&self) -> Term {
+        encode(self.as_bytes())
+    }
+}
+
+impl LambdaDecode for String {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        if term != fls() && !is_list(&term) {
+            return Err(Unexpected("string", term));
+        }
+
+        let bytes = vectorize_list(term)
+            .into_iter()
+            .map(decode_byte)
+            .collect::<Result<Vec<u8>, Error>>()?;
+
+        String::from_utf8(bytes).map_err(|e| Unexpected("string", encode(e.as_bytes())))
+    }
+}
+
+impl<T: LambdaEncode> LambdaEncode for Box<T> {
+    fn lambda_encode(&self) -> Term {
+        (**self).lambda_encode()
+    }
+}
+
+impl<T: LambdaDecode> LambdaDecode for Box<T> {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        T::lambda_decode(term).map(Box::new)
+    }
+}
+
+/// The term is passed as is, so it should be closed.
+impl LambdaEncode for Term {
+    fn lambda_encode(&self) -> Term {
+        self.clone()
+    }
+}
+
+impl LambdaDecode for Term {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        Ok(term)
+    }
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
     use crate::encoding::binary::from_bits;
+    use lambda_calculus::data::num::church::{is_zero, pred};
+    use lambda_calculus::{IntoChurchNum, NOR};
+
+    fn round_trip<T: LambdaEncode + LambdaDecode + PartialEq + fmt::Debug>(value: T) {
+        assert_eq!(T::lambda_decode(value.lambda_encode()), Ok(value));
+    }
+
+    #[test]
+    fn typed_round_trips() {
+        round_trip(true);
+        round_trip(false);
+        round_trip(0u8);
+        round_trip(255u8);
+        round_trip(1000u16);
+        round_trip(7u32);
+        round_trip(3u64);
+        round_trip(12usize);
+        round_trip(Vec::<bool>::new());
+        round_trip(vec![1u8, 2, 3]);
+        round_trip(vec![vec![true], vec![], vec![false, true]]);
+        round_trip(None::<u8>);
+        round_trip(Some(Some(2u8)));
+        round_trip((1u8, false));
+        round_trip((
+            String::from("zażółć"),
+            vec![Some(3u32), None],
+            (true, 0u8, 1u8),
+        ));
+        round_trip((1u8, 2u8, 3u8, 4u8, 5u8));
+        round_trip(abs(Var(1)));
+    }
+
+    #[test]
+    fn typed_encodings() {
+        assert_eq!(5u8.lambda_encode(), 5.into_church());
+        assert_eq!(true.lambda_encode(), tru());
+        assert_eq!(String::from("a").lambda_encode(), encode(b"a"));
+
+        let mut is_empty = app(is_zero(), 0u64.lambda_encode());
+        is_empty.reduce(NOR, 0);
+        assert_eq!(bool::lambda_decode(is_empty), Ok(true));
+
+        let mut smaller = app(pred(), 10usize.lambda_encode());
+        smaller.reduce(NOR, 0);
+        assert_eq!(usize::lambda_decode(smaller), Ok(9));
+    }
+
+    #[test]
+    fn typed_errors() {
+        assert_eq!(
+            u8::lambda_decode(256u16.lambda_encode()),
+            Err(Unexpected("u8", 256.into_church()))
+        );
+        assert_eq!(bool::lambda_decode(Var(1)), Err(Unexpected("bool", Var(1))));
+        assert_eq!(
+            <(bool, bool)>::lambda_decode((true, false, true).lambda_encode()),
+            Err(Unexpected("tuple", (true, false, true).lambda_encode()))
+        );
+        assert_eq!(
+            Option::<bool>::lambda_decode(abs!(2, app(Var(1), Var(2)))),
+            Err(Unexpected("option", abs!(2, app(Var(1), Var(2)))))
+        );
+        assert_eq!(
+            String::lambda_decode(encode(b"\xff")),
+            Err(Unexpected("string", encode(b"\xff")))
+        );
+        assert_eq!(
+            Vec::<u8>::lambda_decode(tru()),
+            Err(Unexpected("list", tru()))
+        );
+    }
 
     #[test]
     fn encoding_lambda() {
@@ -120,6 +569,14 @@ mod test {
         assert_eq!(decode(k).unwrap(), "(λλ2)");
         assert_eq!(decode(s).unwrap(), "(λλλ31(21))");
         assert_eq!(decode(quine).unwrap(), "(λ1((λ11)(λλλλλ14(3(55)2)))1)");
+
+        // bits followed by something other than the end of the list
+        let bits = abs(app!(
+            Var(1),
+            fls(),
+            abs(app!(Var(1), tru(), abs!(3, Var(1))))
+        ));
+        assert_eq!(decode(bits).unwrap(), "10(λλλ1)");
     }
 
     #[test]
@@ -132,4 +589,15 @@ mod test {
         );
         assert_eq!(decode(encode(b"\0(1)")).unwrap(), "\0(1)");
     }
+
+    #[test]
+    fn long_input() {
+        // lists consisting of millions of nodes
+        let input = b"herp derp ".repeat(1 << 13);
+        let bits = b"0110".repeat(1 << 16);
+
+        assert_eq!(decode(encode(&input)).unwrap().as_bytes(), &input[..]);
+        assert_eq!(decode(encode(&bits)).unwrap().as_bytes(), &bits[..]);
+        assert_eq!(vectorize_list(encode(&input)).len(), input.len());
+    }
 }
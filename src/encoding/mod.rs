@@ -1,4 +1,10 @@
 //! BLC-relevant encodings
 
+pub mod bcl;
 pub mod binary;
+pub mod bits;
+pub mod container;
+pub mod entropy;
 pub mod lambda;
+pub mod numeral;
+pub mod scheme;
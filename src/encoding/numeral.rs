@@ -0,0 +1,399 @@
+//! Lambda encodings of natural numbers
+//!
+//! | numeral   | 0     | n + 1                 | size     |
+//! |-----------|-------|-----------------------|----------|
+//! | Church    | `λλ1` | `λλ2(2(..(21)))`      | linear   |
+//! | Scott     | `λλ2` | `λλ1 n`               | linear   |
+//! | Parigot   | `λλ1` | `λλ2 n (body of n)`   | exponential (as a tree) |
+//! | binary    | `λλ1` | a list of bits        | logarithmic |
+//!
+//! The Church, Scott and Parigot numerals are the same as the ones from
+//! `lambda_calculus::data::num`. Binary numerals are single-pair lists of bits, the least
+//! significant one first and without trailing zeros (though they are accepted when decoding);
+//! the bits are lambda-encoded the same way as in byte input (`0` is `λλ2`, `1` is `λλ1`).
+//!
+//! Arithmetic on binary numerals takes time logarithmic in their values, which makes them a better
+//! fit for programs working with large numbers; `Number` can be used to pass any kind of numeral to
+//! `execution::run_typed` and to recognize the kind of the numeral it returns. With the
+//! `num-bigint` feature, `BigNumber` does the same for values that don't fit in a `u64`.
+//!
+//! Only binary numerals are practical for large values: building a Church or Scott numeral takes
+//! time and memory linear in its value, and a Parigot numeral shares no structure between its
+//! copies of the smaller numerals, so its tree doubles in size with each successor (encoding 30
+//! takes over a billion nodes).
+
+use self::Numeral::*;
+use crate::encoding::lambda::{Error, LambdaDecode, LambdaEncode};
+use crate::execution::arithmetic::church;
+use crate::pair_list::{listify_terms, uncons_ref};
+use lambda_calculus::data::boolean::{fls, tru};
+use lambda_calculus::{Term, Term::*, abs, app};
+
+/// A kind of lambda-encoded natural number.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Numeral {
+    /// a Church numeral
+    Church,
+    /// a Scott numeral
+    Scott,
+    /// a Parigot numeral
+    Parigot,
+    /// a little-endian list of bits
+    Binary,
+}
+
+impl Numeral {
+    /// All the kinds of numerals, in the order in which `recognize` tries them.
+    pub const ALL: [Numeral; 4] = [Church, Scott, Parigot, Binary];
+
+    /// Encodes a number as a numeral of this kind.
+    ///
+    /// Mind the sizes of the numerals (see the module documentation): apart from binary ones, they
+    /// are only feasible for small values, Parigot numerals for values up to about 25.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::encoding::numeral::Numeral::*;
+    /// use lambda_calculus::*;
+    ///
+    /// assert_eq!(Church.encode(3), 3.into_church());
+    /// assert_eq!(Scott.encode(3), 3.into_scott());
+    /// assert_eq!(Parigot.encode(3), 3.into_parigot());
+    /// ```
+    pub fn encode(self, n: u64) -> Term {
+        match self {
+            Church => n.lambda_encode(),
+            Scott => {
+                let mut numeral = abs!(2, Var(2));
+                for _ in 0..n {
+                    numeral = abs!(2, app(Var(1), numeral));
+                }
+                numeral
+            }
+            Parigot => {
+                let (mut numeral, mut body) = (abs!(2, Var(1)), Var(1));
+                for _ in 0..n {
+                    body = app!(Var(2), numeral, body);
+                    numeral = abs!(2, body.clone());
+                }
+                numeral
+            }
+            Binary => binary((0..u64::BITS - n.leading_zeros()).map(|i| (n >> i) & 1 == 1)),
+        }
+    }
+
+    /// Decodes a numeral of this kind; returns `None` if the term isn't one or if its value
+    /// doesn't fit in a `u64`.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::encoding::numeral::Numeral::*;
+    /// use lambda_calculus::*;
+    ///
+    /// assert_eq!(Binary.decode(&Binary.encode(1000)), Some(1000));
+    /// assert_eq!(Scott.decode(&3.into_church()), None);
+    /// ```
+    pub fn decode(self, term: &Term) -> Option<u64> {
+        match self {
+            Church => church(term).map(|n| n as u64),
+            Scott => {
+                let mut numeral = term;
+                let mut n = 0u64;
+                loop {
+                    match numeral.unabs_ref().and_then(Term::unabs_ref).ok()? {
+                        Var(2) => return Some(n),
+                        App(boxed) if boxed.0 == Var(1) => {
+                            n = n.checked_add(1)?;
+                            numeral = &boxed.1;
+                        }
+                        _ => return None,
+                    }
+                }
+            }
+            Parigot => {
+                let mut numeral = term;
+                let mut n = 0u64;
+                loop {
+                    match numeral.unabs_ref().and_then(Term::unabs_ref).ok()? {
+                        Var(1) => return Some(n),
+                        App(boxed) => match &boxed.0 {
+                            App(inner)
+                                if inner.0 == Var(2)
+                                    && inner.1.unabs_ref().and_then(Term::unabs_ref)
+                                        == Ok(&boxed.1) =>
+                            {
+                                n = n.checked_add(1)?;
+                                numeral = &inner.1;
+                            }
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                }
+            }
+            Binary => {
+                let mut n = 0u64;
+                for (i, bit) in bits(term)?.into_iter().enumerate() {
+                    if bit {
+                        if i >= 64 {
+                            return None;
+                        }
+                        n |= 1 << i;
+                    }
+                }
+                Some(n)
+            }
+        }
+    }
+
+    /// Encodes a big number as a numeral of this kind.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the numeral isn't binary and the value doesn't fit in a `u64`; such a numeral
+    /// wouldn't fit in memory anyway.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::encoding::numeral::Numeral::*;
+    /// use num_bigint::BigUint;
+    ///
+    /// assert_eq!(Scott.encode_big(&BigUint::from(3u8)), Scott.encode(3));
+    /// let big = BigUint::from(1u8) << 100;
+    /// assert_eq!(Binary.decode_big(&Binary.encode_big(&big)), Some(big));
+    /// ```
+    #[cfg(feature = "num-bigint")]
+    pub fn encode_big(self, n: &num_bigint::BigUint) -> Term {
+        match self {
+            Binary => binary((0..n.bits()).map(|i| n.bit(i))),
+            _ => self.encode(
+                u64::try_from(n).expect("only binary numerals can represent values over u64::MAX"),
+            ),
+        }
+    }
+
+    /// Decodes a numeral of this kind as a big number; returns `None` if the term isn't one.
+    #[cfg(feature = "num-bigint")]
+    pub fn decode_big(self, term: &Term) -> Option<num_bigint::BigUint> {
+        match self {
+            Binary => {
+                let mut n = num_bigint::BigUint::default();
+                for (i, bit) in bits(term)?.into_iter().enumerate() {
+                    n.set_bit(i as u64, bit);
+                }
+                Some(n)
+            }
+            // the other numerals are too large to be built with values over u64::MAX
+            _ => self.decode(term).map(Into::into),
+        }
+    }
+}
+
+// encodes little-endian bits as a binary numeral, omitting the trailing zeros
+fn binary<I: IntoIterator<Item = bool>>(bits: I) -> Term {
+    let mut bits = bits.into_iter().collect::<Vec<_>>();
+    while bits.last() == Some(&false) {
+        bits.pop();
+    }
+
+    listify_terms(
+        bits.into_iter()
+            .map(|bit| if bit { fls() } else { tru() })
+            .collect(),
+    )
+}
+
+// decodes the little-endian bits of a binary numeral
+fn bits(term: &Term) -> Option<Vec<bool>> {
+    let mut bits = Vec::new();
+    let mut list = term;
+
+    while *list != fls() {
+        let (head, tail) = uncons_ref(list).ok()?;
+        bits.push(if *head == fls() {
+            true
+        } else if *head == tru() {
+            false
+        } else {
+            return None;
+        });
+        list = tail;
+    }
+
+    Some(bits)
+}
+
+/// Recognizes a numeral of any kind, returning its kind and value. Since some numerals have
+/// multiple interpretations (e.g. `λλ1` is 0 as a Church, Parigot and binary numeral), the kinds
+/// are tried in the order of `Numeral::ALL`.
+///
+/// # Example
+/// ```
+/// use blc::encoding::numeral::{recognize, Numeral::*};
+/// use lambda_calculus::{Term::Var, abs};
+///
+/// assert_eq!(recognize(&Binary.encode(6)), Some((Binary, 6)));
+/// assert_eq!(recognize(&Scott.encode(6)), Some((Scott, 6)));
+/// assert_eq!(recognize(&abs(Var(1))), None);
+/// ```
+pub fn recognize(term: &Term) -> Option<(Numeral, u64)> {
+    Numeral::ALL
+        .into_iter()
+        .find_map(|numeral| numeral.decode(term).map(|n| (numeral, n)))
+}
+
+/// A natural number along with the kind of numeral that represents it when it is lambda-encoded;
+/// any kind of numeral can be decoded as it.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::to_bits;
+/// use blc::encoding::numeral::{Number, Numeral::Binary};
+/// use blc::execution::run_typed;
+/// use lambda_calculus::data::boolean::tru;
+/// use lambda_calculus::{Term::Var, abs, app};
+///
+/// // λ. λ1 0 1 - prepends a 0 bit, doubling a binary numeral
+/// let double = to_bits(&abs(abs(app!(Var(1), tru(), Var(2)))));
+/// let n = Number { numeral: Binary, value: 1 << 40 };
+///
+/// assert_eq!(
+///     run_typed(&double, &n),
+///     Ok(Number { numeral: Binary, value: 1 << 41 })
+/// );
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Number {
+    /// the kind of numeral
+    pub numeral: Numeral,
+    /// the value
+    pub value: u64,
+}
+
+impl LambdaEncode for Number {
+    fn lambda_encode(&self) -> Term {
+        self.numeral.encode(self.value)
+    }
+}
+
+impl LambdaDecode for Number {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        match recognize(&term) {
+            Some((numeral, value)) => Ok(Number { numeral, value }),
+            None => Err(Error::Unexpected("numeral", term)),
+        }
+    }
+}
+
+/// A big natural number along with the kind of numeral that represents it; the counterpart of
+/// `Number` for values that don't fit in a `u64`.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::to_bits;
+/// use blc::encoding::numeral::{BigNumber, Numeral::Scott};
+/// use blc::execution::run_typed;
+/// use lambda_calculus::{Term::Var, abs};
+/// use num_bigint::BigUint;
+///
+/// let n = BigNumber { numeral: Scott, value: BigUint::from(5u8) };
+///
+/// assert_eq!(run_typed(&to_bits(&abs(Var(1))), &n), Ok(n));
+/// ```
+#[cfg(feature = "num-bigint")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BigNumber {
+    /// the kind of numeral
+    pub numeral: Numeral,
+    /// the value
+    pub value: num_bigint::BigUint,
+}
+
+#[cfg(feature = "num-bigint")]
+impl LambdaEncode for BigNumber {
+    fn lambda_encode(&self) -> Term {
+        self.numeral.encode_big(&self.value)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl LambdaDecode for BigNumber {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        let recognized = Numeral::ALL
+            .into_iter()
+            .find_map(|numeral| numeral.decode_big(&term).map(|value| (numeral, value)));
+
+        match recognized {
+            Some((numeral, value)) => Ok(BigNumber { numeral, value }),
+            None => Err(Error::Unexpected("numeral", term)),
+        }
+    }
+}
+
+/// Big integers are lambda-encoded as binary numerals; any kind of numeral can be decoded as one.
+#[cfg(feature = "num-bigint")]
+impl LambdaEncode for num_bigint::BigUint {
+    fn lambda_encode(&self) -> Term {
+        Binary.encode_big(self)
+    }
+}
+
+#[cfg(feature = "num-bigint")]
+impl LambdaDecode for num_bigint::BigUint {
+    fn lambda_decode(term: Term) -> Result<Self, Error> {
+        BigNumber::lambda_decode(term).map(|number| number.value)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambda_calculus::data::num::{parigot, scott};
+    use lambda_calculus::reduction::Order::NOR;
+
+    #[test]
+    fn round_trips() {
+        for numeral in Numeral::ALL {
+            let max = if numeral == Parigot { 12 } else { 300 };
+            for n in 0..max {
+                assert_eq!(numeral.decode(&numeral.encode(n)), Some(n), "{:?}", numeral);
+            }
+        }
+
+        for n in [u32::MAX as u64, 1 << 63, u64::MAX] {
+            assert_eq!(Binary.decode(&Binary.encode(n)), Some(n));
+        }
+    }
+
+    #[test]
+    fn binary_numerals() {
+        assert_eq!(Binary.encode(0), fls());
+        assert_eq!(Binary.encode(6), listify_terms(vec![tru(), fls(), fls()]));
+        assert_eq!(
+            Binary.decode(&listify_terms(vec![fls(), tru(), tru()])),
+            Some(1)
+        );
+        assert_eq!(Binary.decode(&listify_terms(vec![fls(); 65])), None);
+        assert_eq!(Binary.decode(&listify_terms(vec![Var(1)])), None);
+    }
+
+    #[test]
+    fn compatible_arithmetic() {
+        let mut scott_sum = app!(scott::add(), Scott.encode(2), Scott.encode(3));
+        scott_sum.reduce(NOR, 0);
+        assert_eq!(Scott.decode(&scott_sum), Some(5));
+
+        let mut parigot_sum = app!(parigot::add(), Parigot.encode(2), Parigot.encode(3));
+        parigot_sum.reduce(NOR, 0);
+        assert_eq!(Parigot.decode(&parigot_sum), Some(5));
+    }
+
+    #[test]
+    fn recognizing() {
+        assert_eq!(recognize(&fls()), Some((Church, 0)));
+        assert_eq!(recognize(&tru()), Some((Scott, 0)));
+        assert_eq!(recognize(&Parigot.encode(4)), Some((Parigot, 4)));
+        assert_eq!(recognize(&Binary.encode(5)), Some((Binary, 5)));
+        assert_eq!(recognize(&abs!(2, app(Var(1), Var(2)))), None);
+    }
+}
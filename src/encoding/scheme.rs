@@ -0,0 +1,367 @@
+//! Pluggable lambda encodings of byte input and output
+//!
+//! By default, byte input is passed to programs and their output is decoded the way described in
+//! the `lambda` module: as single-pair lists of bytes, each of them a list of 8 Church booleans,
+//! the most significant bit first and `0` being `λλ2`. Programs written for other conventions can
+//! be run with a different `Encoding` (see `execution::Options::encoding`); `Scheme` covers the
+//! common ones:
+//!
+//! | `List`    | empty list | `head : tail`    |
+//! |-----------|------------|------------------|
+//! | `Pair`    | `λλ1`      | `λ1 head tail`   |
+//! | `Scott`   | `λλ2`      | `λλ1 head tail`  |
+//! | `Church`  | `λλ2`      | `λλ1 head (tail body)`, i.e. a right fold |
+//!
+//! | `Byte`    | encoding                                                          |
+//! |-----------|-------------------------------------------------------------------|
+//! | `Bits`    | a list of 8 Church booleans; the bit order and polarity can be chosen |
+//! | `Numeral` | a Church numeral                                                  |
+//!
+//! The lists are the same as the ones from `lambda_calculus::data::list`; the bits of a byte are
+//! listed the same way as the bytes.
+
+use self::Byte::*;
+use self::List::*;
+use crate::encoding::lambda::{self, Error};
+use crate::encoding::numeral;
+use crate::pair_list::{listify_terms, uncons_ref};
+use lambda_calculus::data::boolean::{fls, tru};
+use lambda_calculus::{Term, Term::*, abs, app};
+use std::fmt;
+
+/// A lambda encoding of byte input and output.
+pub trait Encoding: fmt::Debug {
+    /// Encodes bytes as a lambda `Term`.
+    fn encode(&self, input: &[u8]) -> Term;
+
+    /// Decodes lambda-encoded output as a `String`.
+    fn decode(&self, term: Term) -> Result<String, Error>;
+
+    /// Encodes a sequence of byte strings (e.g. command-line arguments) as a lambda `Term`; by
+    /// default, as a single-pair list of byte strings encoded with `encode`.
+    fn encode_args(&self, args: &[&[u8]]) -> Term {
+        listify_terms(args.iter().map(|arg| self.encode(arg)).collect())
+    }
+}
+
+/// The default encoding: `lambda::encode` and `lambda::decode`. Unlike `Scheme`, it decodes any
+/// output, falling back to displaying the terms it doesn't recognize.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Standard;
+
+impl Encoding for Standard {
+    fn encode(&self, input: &[u8]) -> Term {
+        lambda::encode(input)
+    }
+
+    fn decode(&self, term: Term) -> Result<String, Error> {
+        lambda::decode(term)
+    }
+
+    fn encode_args(&self, args: &[&[u8]]) -> Term {
+        lambda::encode_args(args)
+    }
+}
+
+/// A lambda encoding of lists.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum List {
+    /// single-pair lists
+    #[default]
+    Pair,
+    /// Scott-encoded lists
+    Scott,
+    /// Church-encoded lists (right folds)
+    Church,
+}
+
+impl List {
+    /// Encodes the given terms as a list.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::encoding::scheme::List::*;
+    /// use lambda_calculus::{IntoChurchList, IntoChurchNum, IntoPairList, IntoScottList};
+    ///
+    /// let items = || vec![1.into_church(), 2.into_church()];
+    ///
+    /// assert_eq!(Pair.encode(items()), items().into_pair_list());
+    /// assert_eq!(Scott.encode(items()), items().into_scott());
+    /// assert_eq!(Church.encode(items()), items().into_church());
+    /// ```
+    pub fn encode(self, items: Vec<Term>) -> Term {
+        match self {
+            Pair => listify_terms(items),
+            Scott => items.into_iter().rev().fold(abs!(2, Var(2)), |tail, head| {
+                abs!(2, app!(Var(1), head, tail))
+            }),
+            Church => abs!(
+                2,
+                items
+                    .into_iter()
+                    .rev()
+                    .fold(Var(2), |tail, head| app!(Var(1), head, tail))
+            ),
+        }
+    }
+
+    /// Returns the items of a list encoded this way; returns `None` if the term isn't one.
+    pub fn decode(self, term: &Term) -> Option<Vec<&Term>> {
+        let mut items = Vec::new();
+
+        match self {
+            Pair => {
+                let mut list = term;
+                while *list != fls() {
+                    let (head, tail) = uncons_ref(list).ok()?;
+                    items.push(head);
+                    list = tail;
+                }
+            }
+            Scott => {
+                let mut list = term;
+                loop {
+                    match list.unabs_ref().and_then(Term::unabs_ref).ok()? {
+                        Var(2) => break,
+                        App(boxed) => match &boxed.0 {
+                            App(inner) if inner.0 == Var(1) => {
+                                items.push(&inner.1);
+                                list = &boxed.1;
+                            }
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                }
+            }
+            Church => {
+                let mut body = term.unabs_ref().and_then(Term::unabs_ref).ok()?;
+                loop {
+                    match body {
+                        Var(2) => break,
+                        App(boxed) => match &boxed.0 {
+                            App(inner) if inner.0 == Var(1) => {
+                                items.push(&inner.1);
+                                body = &boxed.1;
+                            }
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                }
+            }
+        }
+
+        Some(items)
+    }
+}
+
+/// A lambda encoding of bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Byte {
+    /// a list of 8 Church booleans
+    Bits {
+        /// whether the least significant bit comes first
+        lsb_first: bool,
+        /// whether `0` is `λλ1` and `1` is `λλ2` instead of the other way round
+        inverted: bool,
+    },
+    /// a Church numeral
+    Numeral,
+}
+
+impl Default for Byte {
+    fn default() -> Self {
+        Bits {
+            lsb_first: false,
+            inverted: false,
+        }
+    }
+}
+
+/// An `Encoding` of byte strings as lists of bytes. Its default is equivalent to `Standard`,
+/// except that it only decodes output that is a list of bytes.
+///
+/// # Example
+/// ```
+/// use blc::encoding::scheme::{Byte, Encoding, List, Scheme};
+/// use lambda_calculus::{IntoChurchNum, IntoScottList};
+///
+/// let scheme = Scheme { list: List::Scott, byte: Byte::Numeral };
+/// let encoded = vec![104.into_church(), 105.into_church()].into_scott();
+///
+/// assert_eq!(scheme.encode(b"hi"), encoded);
+/// assert_eq!(scheme.decode(encoded), Ok("hi".into()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Scheme {
+    /// the encoding of the list of bytes (and of the bits of a byte)
+    pub list: List,
+    /// the encoding of a byte
+    pub byte: Byte,
+}
+
+impl Scheme {
+    /// Encodes a single byte.
+    pub fn encode_byte(&self, byte: u8) -> Term {
+        match self.byte {
+            Bits {
+                lsb_first,
+                inverted,
+            } => {
+                let bits = (0..8).map(|i| {
+                    let shift = if lsb_first { i } else { 7 - i };
+                    if ((byte >> shift) & 1 == 1) != inverted {
+                        fls()
+                    } else {
+                        tru()
+                    }
+                });
+                self.list.encode(bits.collect())
+            }
+            Numeral => numeral::Numeral::Church.encode(byte.into()),
+        }
+    }
+
+    /// Decodes a single byte; returns `None` if the term isn't one.
+    pub fn decode_byte(&self, term: &Term) -> Option<u8> {
+        match self.byte {
+            Bits {
+                lsb_first,
+                inverted,
+            } => {
+                let bits = self.list.decode(term).filter(|bits| bits.len() == 8)?;
+                let mut byte = 0;
+                for (i, bit) in bits.into_iter().enumerate() {
+                    let one = if *bit == fls() {
+                        !inverted
+                    } else if *bit == tru() {
+                        inverted
+                    } else {
+                        return None;
+                    };
+                    let shift = if lsb_first { i } else { 7 - i };
+                    byte |= u8::from(one) << shift;
+                }
+                Some(byte)
+            }
+            Numeral => numeral::Numeral::Church
+                .decode(term)
+                .and_then(|n| u8::try_from(n).ok()),
+        }
+    }
+}
+
+impl Encoding for Scheme {
+    fn encode(&self, input: &[u8]) -> Term {
+        self.list
+            .encode(input.iter().map(|&byte| self.encode_byte(byte)).collect())
+    }
+
+    fn decode(&self, term: Term) -> Result<String, Error> {
+        let Some(bytes) = self.list.decode(&term) else {
+            return Err(Error::Unexpected("list", term));
+        };
+
+        bytes
+            .into_iter()
+            .map(|byte| match self.decode_byte(byte) {
+                Some(byte) => Ok(char::from(byte)),
+                None => Err(Error::NotAByte(byte.clone())),
+            })
+            .collect()
+    }
+
+    fn encode_args(&self, args: &[&[u8]]) -> Term {
+        self.list
+            .encode(args.iter().map(|arg| self.encode(arg)).collect())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::lambda::{decode_byte, encode, encode_byte};
+    use lambda_calculus::IntoChurchNum;
+
+    const INVERTED_LSB: Scheme = Scheme {
+        list: Pair,
+        byte: Bits {
+            lsb_first: true,
+            inverted: true,
+        },
+    };
+
+    #[test]
+    fn default_scheme() {
+        let scheme = Scheme::default();
+
+        for byte in 0..=255 {
+            assert_eq!(scheme.encode_byte(byte), encode_byte(byte));
+            assert_eq!(scheme.decode_byte(&encode_byte(byte)), Some(byte));
+        }
+        assert_eq!(scheme.encode(b"herp"), encode(b"herp"));
+        assert_eq!(scheme.decode(encode(b"herp")), Ok("herp".into()));
+    }
+
+    #[test]
+    fn bit_order_and_polarity() {
+        // 0x01, the least significant bit first and inverted: 1 0 0 0 0 0 0 0 as λλ2 λλ1 .. λλ1
+        let mut bits = vec![fls(); 8];
+        bits[0] = tru();
+        assert_eq!(INVERTED_LSB.encode_byte(1), listify_terms(bits));
+        assert_eq!(
+            decode_byte(INVERTED_LSB.encode_byte(0b1110_0000)),
+            Ok(0b1111_1000)
+        );
+
+        for byte in 0..=255 {
+            assert_eq!(
+                INVERTED_LSB.decode_byte(&INVERTED_LSB.encode_byte(byte)),
+                Some(byte)
+            );
+        }
+    }
+
+    #[test]
+    fn lists() {
+        for list in [Pair, Scott, Church] {
+            for byte in [
+                Bits {
+                    lsb_first: false,
+                    inverted: false,
+                },
+                Numeral,
+            ] {
+                let scheme = Scheme { list, byte };
+                assert_eq!(scheme.decode(scheme.encode(b"")), Ok("".into()));
+                assert_eq!(scheme.decode(scheme.encode(b"herp")), Ok("herp".into()));
+            }
+        }
+    }
+
+    #[test]
+    fn decoding_errors() {
+        let scott = Scheme {
+            list: Scott,
+            ..Scheme::default()
+        };
+
+        assert_eq!(
+            scott.decode(encode(b"a")),
+            Err(Error::Unexpected("list", encode(b"a")))
+        );
+        assert_eq!(
+            scott.decode(Scott.encode(vec![Var(1)])),
+            Err(Error::NotAByte(Var(1)))
+        );
+        assert_eq!(
+            Scheme {
+                list: Pair,
+                byte: Numeral
+            }
+            .decode(listify_terms(vec![256.into_church()])),
+            Err(Error::NotAByte(256.into_church()))
+        );
+    }
+}
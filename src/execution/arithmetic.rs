@@ -0,0 +1,515 @@
+//! Accelerated Church numeral arithmetic
+//!
+//! The normal-order reduction performed here recognizes applications of the standard Church
+//! numeral combinators from `lambda_calculus::data::num::church` and, if their arguments reduce to
+//! Church numerals, computes their results natively instead of reducing them step by step. The
+//! resulting normal forms are the same as the ones produced by plain reduction.
+//!
+//! The numbers are computed as `usize`s: a Church numeral takes memory proportional to its value,
+//! so any argument fits in one, while a result that doesn't (e.g. a large power) couldn't be built
+//! as a term either. Such applications are left to plain reduction, like the ones of `div`,
+//! `quot` and `rem` to numerals their results aren't defined for. `pred` and `sub` saturate at 0,
+//! just like the combinators do.
+//!
+//! In order to find out whether the arguments of a combinator are numerals, they are reduced in
+//! place (in normal order, from the first one) before the combinator is applied, which plain
+//! normal-order reduction wouldn't do. This doesn't change the normal form, but the reductions
+//! count towards the limit, and up to 65536 of them can be spent on an argument that doesn't turn
+//! out to be a numeral; e.g. `mul 0 Ω` is only reduced to `0` after its `Ω` has been given 65536
+//! reductions.
+
+use lambda_calculus::data::num::church::*;
+use lambda_calculus::*;
+use std::mem;
+
+/// The maximum number of reductions spent on determining whether an argument of an arithmetic
+/// combinator is a Church numeral; they are performed in place, so they aren't repeated if the
+/// argument isn't one, but they still count towards the limit.
+const ARGUMENT_LIMIT: usize = 1 << 16;
+
+struct Combinator {
+    term: Term,
+    size: usize,
+    arity: usize,
+    eval: fn(&[usize]) -> Option<Term>,
+}
+
+fn combinators() -> Vec<Combinator> {
+    fn numeral(n: Option<usize>) -> Option<Term> {
+        n.map(|n| n.into_church())
+    }
+
+    let mut combinators = vec![
+        Combinator {
+            term: is_zero(),
+            size: 0,
+            arity: 1,
+            eval: |n| Some((n[0] == 0).into()),
+        },
+        Combinator {
+            term: succ(),
+            size: 0,
+            arity: 1,
+            eval: |n| numeral(n[0].checked_add(1)),
+        },
+        Combinator {
+            term: pred(),
+            size: 0,
+            arity: 1,
+            eval: |n| numeral(Some(n[0].saturating_sub(1))),
+        },
+        Combinator {
+            term: add(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(n[0].checked_add(n[1])),
+        },
+        Combinator {
+            term: sub(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(Some(n[0].saturating_sub(n[1]))),
+        },
+        Combinator {
+            term: mul(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(n[0].checked_mul(n[1])),
+        },
+        Combinator {
+            term: pow(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(u32::try_from(n[1]).ok().and_then(|e| n[0].checked_pow(e))),
+        },
+        Combinator {
+            term: lt(),
+            size: 0,
+            arity: 2,
+            eval: |n| Some((n[0] < n[1]).into()),
+        },
+        Combinator {
+            term: leq(),
+            size: 0,
+            arity: 2,
+            eval: |n| Some((n[0] <= n[1]).into()),
+        },
+        Combinator {
+            term: eq(),
+            size: 0,
+            arity: 2,
+            eval: |n| Some((n[0] == n[1]).into()),
+        },
+        Combinator {
+            term: neq(),
+            size: 0,
+            arity: 2,
+            eval: |n| Some((n[0] != n[1]).into()),
+        },
+        Combinator {
+            term: geq(),
+            size: 0,
+            arity: 2,
+            eval: |n| Some((n[0] >= n[1]).into()),
+        },
+        Combinator {
+            term: gt(),
+            size: 0,
+            arity: 2,
+            eval: |n| Some((n[0] > n[1]).into()),
+        },
+        Combinator {
+            term: div(),
+            size: 0,
+            arity: 2,
+            eval: |n| {
+                let (q, r) = (n[0].checked_div(n[1])?, n[0].checked_rem(n[1])?);
+                Some((q, r).into_church())
+            },
+        },
+        Combinator {
+            term: quot(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(n[0].checked_div(n[1])),
+        },
+        Combinator {
+            term: rem(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(n[0].checked_rem(n[1])),
+        },
+        Combinator {
+            term: min(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(Some(n[0].min(n[1]))),
+        },
+        Combinator {
+            term: max(),
+            size: 0,
+            arity: 2,
+            eval: |n| numeral(Some(n[0].max(n[1]))),
+        },
+        Combinator {
+            term: is_even(),
+            size: 0,
+            arity: 1,
+            eval: |n| Some((n[0] % 2 == 0).into()),
+        },
+        Combinator {
+            term: is_odd(),
+            size: 0,
+            arity: 1,
+            eval: |n| Some((n[0] % 2 == 1).into()),
+        },
+        Combinator {
+            term: fac(),
+            size: 0,
+            arity: 1,
+            eval: |n| numeral((1..=n[0]).try_fold(1usize, |acc, k| acc.checked_mul(k))),
+        },
+    ];
+
+    for combinator in &mut combinators {
+        combinator.size = size(&combinator.term, usize::MAX);
+    }
+    combinators
+}
+
+/// Performs normal-order β-reduction on a `Term` with accelerated Church numeral arithmetic and an
+/// optional limit on the number of reductions (`0` means no limit); returns the number of performed
+/// reductions, with each natively computed combinator application counted as one.
+///
+/// # Example
+/// ```
+/// use blc::execution::arithmetic::reduce;
+/// use lambda_calculus::data::num::church::rem;
+/// use lambda_calculus::*;
+///
+/// let mut term = app!(rem(), 1000.into_church(), 7.into_church());
+/// reduce(&mut term, 0);
+///
+/// assert_eq!(term, 6.into_church());
+/// ```
+pub fn reduce(term: &mut Term, limit: usize) -> usize {
+    let combinators = combinators();
+    let mut reducer = Reducer {
+        combinators: &combinators,
+        count: 0,
+        limit,
+    };
+
+    reducer.nor(term);
+    reducer.count
+}
+
+struct Reducer<'a> {
+    combinators: &'a [Combinator],
+    count: usize,
+    limit: usize,
+}
+
+impl<'a> Reducer<'a> {
+    fn is_exhausted(&self) -> bool {
+        self.limit != 0 && self.count >= self.limit
+    }
+
+    fn nor(&mut self, mut term: &mut Term) {
+        loop {
+            if self.is_exhausted() {
+                return;
+            }
+
+            match term {
+                Abs(_) => term = term.unabs_mut().unwrap(), // safe
+                App(_) => {
+                    if self.accelerate(term) {
+                        continue;
+                    }
+
+                    self.cbn(term.lhs_mut().unwrap()); // safe
+                    if is_redex(term) && !self.is_exhausted() {
+                        self.contract(term);
+                    } else {
+                        let (lhs, rhs) = term.unapp_mut().unwrap(); // safe
+                        self.nor(lhs);
+                        term = rhs;
+                    }
+                }
+                Var(_) => return,
+            }
+        }
+    }
+
+    fn cbn(&mut self, term: &mut Term) {
+        while !self.is_exhausted() && matches!(term, App(_)) {
+            if self.accelerate(term) {
+                continue;
+            }
+
+            self.cbn(term.lhs_mut().unwrap()); // safe
+            if is_redex(term) && !self.is_exhausted() {
+                self.contract(term);
+            } else {
+                return;
+            }
+        }
+    }
+
+    fn contract(&mut self, term: &mut Term) {
+        let (mut lhs, rhs) = mem::replace(term, Var(0)).unapp().unwrap(); // safe; a redex
+        lhs.apply(&rhs).unwrap(); // safe; a redex
+        *term = lhs;
+        self.count += 1;
+    }
+
+    // replaces an application of an arithmetic combinator to Church numerals with its result
+    fn accelerate(&mut self, term: &mut Term) -> bool {
+        let Some(combinator) = self.recognize(term) else {
+            return false;
+        };
+
+        let mut values = Vec::with_capacity(combinator.arity);
+        for arg in args_mut(term).into_iter().take(combinator.arity) {
+            match self.numeral(arg) {
+                Some(n) => values.push(n),
+                None => return false,
+            }
+        }
+
+        if let Some(result) = (combinator.eval)(&values) {
+            let mut args = unspine(mem::replace(term, Var(0)));
+            *term = args
+                .split_off(combinator.arity)
+                .into_iter()
+                .fold(result, app);
+            self.count += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    // the combinator at the head of an application with enough arguments for it
+    fn recognize(&self, term: &Term) -> Option<&'a Combinator> {
+        let (head, args) = spine(term);
+        if let Var(_) = head {
+            return None;
+        }
+
+        // comparing the sizes first is cheap, as they are bounded by the largest combinator
+        let largest = self.combinators.iter().map(|c| c.size).max().unwrap_or(0);
+        let head_size = size(head, largest);
+
+        self.combinators.iter().find(|combinator| {
+            args.len() >= combinator.arity
+                && head_size == combinator.size
+                && *head == combinator.term
+        })
+    }
+
+    // reduces an argument of an arithmetic combinator in place, keeping the reductions even if it
+    // doesn't turn out to be a numeral, and returns its value if it does
+    fn numeral(&mut self, term: &mut Term) -> Option<usize> {
+        if let Some(n) = church(term) {
+            return Some(n);
+        }
+
+        let mut reducer = Reducer {
+            combinators: self.combinators,
+            count: 0,
+            limit: if self.limit == 0 {
+                ARGUMENT_LIMIT
+            } else {
+                ARGUMENT_LIMIT.min(self.limit - self.count)
+            },
+        };
+        reducer.nor(term);
+        self.count += reducer.count;
+
+        if reducer.is_exhausted() {
+            None
+        } else {
+            church(term)
+        }
+    }
+}
+
+fn is_redex(term: &Term) -> bool {
+    matches!(term.lhs_ref(), Ok(Abs(_)))
+}
+
+fn spine(term: &Term) -> (&Term, Vec<&Term>) {
+    let mut head = term;
+    let mut args = Vec::new();
+
+    while let App(boxed) = head {
+        args.push(&boxed.1);
+        head = &boxed.0;
+    }
+    args.reverse();
+
+    (head, args)
+}
+
+fn args_mut(term: &mut Term) -> Vec<&mut Term> {
+    let mut head = term;
+    let mut args = Vec::new();
+
+    while let App(boxed) = head {
+        let (lhs, rhs) = &mut **boxed;
+        args.push(rhs);
+        head = lhs;
+    }
+    args.reverse();
+
+    args
+}
+
+// the number of nodes of a term, counting no further than one past the given limit
+fn size(term: &Term, limit: usize) -> usize {
+    let mut size = 0;
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        size += 1;
+        if size > limit {
+            break;
+        }
+        match term {
+            Var(_) => {}
+            Abs(t) => stack.push(t),
+            App(boxed) => {
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
+        }
+    }
+
+    size
+}
+
+fn unspine(mut term: Term) -> Vec<Term> {
+    let mut args = Vec::new();
+
+    while let App(_) = term {
+        let (lhs, rhs) = term.unapp().unwrap(); // safe
+        args.push(rhs);
+        term = lhs;
+    }
+    args.reverse();
+
+    args
+}
+
+pub(crate) fn church(term: &Term) -> Option<usize> {
+    let mut body = term.unabs_ref().ok()?.unabs_ref().ok()?;
+    let mut n = 0;
+
+    loop {
+        match body {
+            Var(1) => return Some(n),
+            App(boxed) if boxed.0 == Var(2) => {
+                n += 1;
+                body = &boxed.1;
+            }
+            _ => return None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn assert_same(term: Term) {
+        let mut accelerated = term.clone();
+        reduce(&mut accelerated, 0);
+
+        assert_eq!(accelerated, beta(term, NOR, 0));
+    }
+
+    #[test]
+    fn unary_combinators() {
+        for combinator in combinators().into_iter().filter(|c| c.arity == 1) {
+            for n in 0..6 {
+                assert_same(app(combinator.term.clone(), n.into_church()));
+            }
+        }
+    }
+
+    #[test]
+    fn binary_combinators() {
+        for combinator in combinators().into_iter().filter(|c| c.arity == 2) {
+            for m in 0..5 {
+                for n in 0..5 {
+                    if n == 0 && [div(), quot(), rem()].contains(&combinator.term) {
+                        continue; // plain reduction doesn't terminate
+                    }
+                    assert_same(app!(
+                        combinator.term.clone(),
+                        m.into_church(),
+                        n.into_church()
+                    ));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn nested_combinators() {
+        assert_same(app!(
+            is_zero(),
+            app!(
+                rem(),
+                app!(mul(), 3.into_church(), 5.into_church()),
+                5.into_church()
+            ),
+            Var(1),
+            Var(2)
+        ));
+        assert_same(abs(app!(add(), Var(1), 2.into_church())));
+    }
+
+    #[test]
+    fn non_numeral_arguments() {
+        // the arguments are reduced before it turns out they aren't numerals
+        let id = || abs(Var(1));
+        assert_same(app!(is_zero(), app(id(), id())));
+        assert_same(app!(add(), app(id(), 2.into_church()), app(id(), Var(1))));
+    }
+
+    #[test]
+    fn saturation() {
+        let mut difference = app!(sub(), 2.into_church(), 5.into_church());
+        reduce(&mut difference, 0);
+        assert_eq!(difference, 0.into_church());
+
+        assert_same(app(pred(), 0.into_church()));
+        assert_same(app!(sub(), 2.into_church(), 5.into_church()));
+    }
+
+    #[test]
+    fn divergent_arguments() {
+        let omega = || app(abs(app(Var(1), Var(1))), abs(app(Var(1), Var(1))));
+
+        // the normal form is the same, but `Ω` uses up reductions before plain reduction takes over
+        let mut term = app!(mul(), 0.into_church(), omega());
+        assert!(reduce(&mut term, 0) > ARGUMENT_LIMIT);
+        assert_eq!(term, beta(app!(mul(), 0.into_church(), omega()), NOR, 0));
+
+        // and it can exhaust a limit that plain reduction would have been within
+        let mut term = app!(mul(), 0.into_church(), omega());
+        assert_eq!(reduce(&mut term, 100), 100);
+        assert_ne!(term, 0.into_church());
+        assert!(app!(mul(), 0.into_church(), omega()).reduce(NOR, 100) < 100);
+    }
+
+    #[test]
+    fn fewer_reductions() {
+        let term = app!(rem(), 20.into_church(), 7.into_church());
+
+        assert!(reduce(&mut term.clone(), 0) < term.clone().reduce(NOR, 0));
+    }
+}
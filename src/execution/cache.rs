@@ -0,0 +1,513 @@
+//! Normal form caching
+//!
+//! Programs often contain large closed subterms (e.g. library functions or precomputed data) that
+//! normalize to the same term on every run. A `Cache` memoizes the normal forms of such subterms,
+//! keyed by a structural hash, so that they can be substituted before the program is executed.
+//! The cache is kept in memory and, optionally, in a directory so that it can also be reused by
+//! other processes.
+//!
+//! Since a term and its normal form are β-equivalent, substituting normal forms doesn't change the
+//! result of a program. Closed subterms that don't reach their normal form within the cache's
+//! reduction limit are left intact; as a program doesn't necessarily reduce all of its subterms
+//! (e.g. `Ω` in `K I Ω`), they are only given a limited number of reductions even if the cache
+//! has no limit.
+//!
+//! The reductions are performed by `lambda_calculus`, which takes a stack frame for every
+//! contraction in a single call and for every level of the reduced term. Subterms are therefore
+//! reduced a few hundred contractions at a time, and they are also left intact if they grow deeper
+//! than `MAX_DEPTH` while being reduced.
+
+use crate::encoding::binary::{from_bits, to_bits};
+use crate::subterms::preorder;
+use lambda_calculus::*;
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The default minimum size (in nodes) of a subterm to be cached.
+const MIN_SIZE: usize = 32;
+
+/// The default maximum number of reductions performed in order to normalize a subterm.
+const LIMIT: usize = 1 << 16;
+
+/// The maximum number of reductions performed by a single call to `Term::reduce`.
+const STEP: usize = 1 << 8;
+
+/// The maximum depth of a term that is being normalized.
+const MAX_DEPTH: usize = 1 << 11;
+
+// a term and its normal form; `None` if one wasn't found within the reduction limit
+type Entry = (Term, Option<Term>);
+
+/// A cache of normal forms of closed lambda terms.
+#[derive(Debug)]
+pub struct Cache {
+    directory: Option<PathBuf>,
+    min_size: usize,
+    limit: usize,
+    entries: Mutex<HashMap<u64, Vec<Entry>>>,
+}
+
+impl Default for Cache {
+    fn default() -> Self {
+        Cache {
+            directory: None,
+            min_size: MIN_SIZE,
+            limit: LIMIT,
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+impl Cache {
+    /// Creates a cache that is only kept in memory.
+    pub fn new() -> Self {
+        Cache::default()
+    }
+
+    /// Creates a cache that is also persisted in the given directory, creating it if necessary.
+    pub fn with_directory<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+
+        Ok(Cache {
+            directory: Some(directory.as_ref().to_path_buf()),
+            ..Cache::default()
+        })
+    }
+
+    /// Sets the minimum size (in nodes) of subterms to be cached.
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Sets the maximum number of reductions that can be performed in order to normalize a
+    /// subterm (`0` means no limit).
+    pub fn limit(mut self, limit: usize) -> Self {
+        self.limit = limit;
+        self
+    }
+
+    /// Returns the number of terms known to the cache in memory.
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().values().map(|v| v.len()).sum()
+    }
+
+    /// Returns `true` if no terms are known to the cache in memory.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the normal form of a closed `Term`, either from the cache or by reducing it and
+    /// caching the result. Returns `None` if the term is not closed or if its normal form can't be
+    /// found within the reduction limit.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::execution::cache::Cache;
+    /// use lambda_calculus::data::num::church::mul;
+    /// use lambda_calculus::*;
+    ///
+    /// let cache = Cache::new();
+    /// let term = app!(mul(), 6.into_church(), 7.into_church());
+    ///
+    /// assert_eq!(cache.normal_form(&term), Some(42.into_church()));
+    /// assert_eq!(cache.len(), 1);
+    /// ```
+    pub fn normal_form(&self, term: &Term) -> Option<Term> {
+        if term.has_free_variables() {
+            return None;
+        }
+
+        self.normal_form_within(term, self.limit).0
+    }
+
+    /// Replaces all the closed subterms of a `Term` that are at least as large as the cache's
+    /// minimum size with their normal forms, if they can be found; returns the number of
+    /// performed reductions.
+    pub fn normalize(&self, term: &mut Term) -> usize {
+        self.normalize_within(term, 0)
+    }
+
+    /// Works like `normalize`, but performs at most `budget` reductions in total (`0` means no
+    /// limit other than the cache's one for each subterm).
+    ///
+    /// # Example
+    /// ```
+    /// use blc::execution::cache::Cache;
+    /// use lambda_calculus::data::num::church::add;
+    /// use lambda_calculus::*;
+    ///
+    /// let cache = Cache::new().min_size(1);
+    /// let mut term = abs(app!(Var(1), app!(add(), 1.into_church(), 2.into_church())));
+    ///
+    /// assert_eq!(cache.normalize_within(&mut term.clone(), 1), 1);
+    /// assert!(cache.normalize_within(&mut term, 0) > 1);
+    /// assert_eq!(term, abs(app(Var(1), 3.into_church())));
+    /// ```
+    pub fn normalize_within(&self, term: &mut Term, budget: usize) -> usize {
+        let subterms = subterms(term);
+        let limit = if self.limit == 0 { LIMIT } else { self.limit };
+        let mut spent = 0;
+
+        // the subterms are identified by their preorder positions
+        let mut stack = vec![(term, 0)];
+        while let Some((term, position)) = stack.pop() {
+            // a limit of 0 would mean no limit for the next subterm
+            if budget != 0 && spent >= budget {
+                break;
+            }
+
+            let subterm = subterms[position];
+            if subterm.is_normal {
+                continue;
+            }
+
+            if subterm.free == 0 && subterm.size >= self.min_size {
+                let limit = if budget == 0 {
+                    limit
+                } else {
+                    limit.min(budget - spent)
+                };
+                let (normal, reductions) = self.normal_form_within(term, limit);
+                spent += reductions;
+
+                if let Some(normal) = normal {
+                    *term = normal;
+                    continue;
+                }
+            }
+
+            match term {
+                Abs(t) => stack.push((t, position + 1)),
+                App(boxed) => {
+                    let (lhs, rhs) = &mut **boxed;
+                    let lhs_size = subterms[position + 1].size;
+                    stack.push((rhs, position + 1 + lhs_size));
+                    stack.push((lhs, position + 1));
+                }
+                Var(_) => {}
+            }
+        }
+
+        spent
+    }
+
+    // the normal form of a closed term found within the given reduction limit (`0` meaning no
+    // limit) and `MAX_DEPTH`, and the number of reductions it took; only failures within the
+    // cache's own limit are remembered
+    fn normal_form_within(&self, term: &Term, limit: usize) -> (Option<Term>, usize) {
+        let hash = hash(term);
+        if let Some(normal) = self.get(hash, term) {
+            return (normal, 0);
+        }
+
+        let mut normal = term.clone();
+        let mut reductions = 0;
+        let normal = loop {
+            let step = if limit == 0 {
+                STEP
+            } else {
+                STEP.min(limit - reductions)
+            };
+            let count = normal.reduce(NOR, step);
+            reductions += count;
+
+            if count < step || is_normal(&normal) {
+                break Some(normal);
+            } else if reductions == limit || depth(&normal) > MAX_DEPTH {
+                break None;
+            }
+        };
+        if normal.is_some() || limit == self.limit {
+            self.insert(hash, term, normal.clone());
+        }
+
+        (normal, reductions)
+    }
+
+    fn get(&self, hash: u64, term: &Term) -> Option<Option<Term>> {
+        let cached = self.entries.lock().unwrap().get(&hash).and_then(|entries| {
+            entries
+                .iter()
+                .find(|(t, _)| t == term)
+                .map(|(_, normal)| normal.clone())
+        });
+        if cached.is_some() {
+            return cached;
+        }
+
+        // only normal forms are persisted; the reduction limit can differ between processes
+        let normal = self.read(hash, term)?;
+        self.entries
+            .lock()
+            .unwrap()
+            .entry(hash)
+            .or_default()
+            .push((term.clone(), Some(normal.clone())));
+
+        Some(Some(normal))
+    }
+
+    fn insert(&self, hash: u64, term: &Term, normal: Option<Term>) {
+        if let Some(ref normal) = normal {
+            self.write(hash, term, normal);
+        }
+
+        self.entries
+            .lock()
+            .unwrap()
+            .entry(hash)
+            .or_default()
+            .push((term.clone(), normal));
+    }
+
+    fn path(&self, hash: u64) -> Option<PathBuf> {
+        self.directory
+            .as_ref()
+            .map(|dir| dir.join(format!("{:016x}.blc", hash)))
+    }
+
+    // the file consists of the BLC-encoded term and its normal form, each on its own line
+    fn read(&self, hash: u64, term: &Term) -> Option<Term> {
+        let contents = fs::read(self.path(hash)?).ok()?;
+        let mut lines = contents.split(|&b| b == b'\n');
+        let (cached, normal) = (lines.next()?, lines.next()?);
+
+        if cached == &to_bits(term)[..] {
+            from_bits(normal).ok()
+        } else {
+            None // a hash collision
+        }
+    }
+
+    // failing to persist an entry only means that it will have to be computed again
+    fn write(&self, hash: u64, term: &Term, normal: &Term) {
+        if let Some(path) = self.path(hash) {
+            if path.exists() {
+                return; // don't replace an entry of a colliding term
+            }
+
+            let mut contents = to_bits(term);
+            contents.push(b'\n');
+            contents.extend(to_bits(normal));
+            contents.push(b'\n');
+
+            let _ = fs::write(path, contents);
+        }
+    }
+}
+
+// a structural hash of a term; unlike the `Hash` implementation of `Term`, its value is fixed, so
+// it can key persisted entries (see `crate::subterms` for why it isn't `store::hash`)
+fn hash(term: &Term) -> u64 {
+    const OFFSET: u64 = 0xcbf29ce484222325;
+    const PRIME: u64 = 0x100000001b3;
+
+    let mut hash = OFFSET;
+    let mut feed = |bytes: &[u8]| {
+        for &byte in bytes {
+            hash ^= byte as u64;
+            hash = hash.wrapping_mul(PRIME);
+        }
+    };
+
+    for term in preorder(term) {
+        match term {
+            Var(i) => {
+                feed(&[0]);
+                feed(&(*i as u64).to_le_bytes());
+            }
+            Abs(_) => feed(&[1]),
+            App(_) => feed(&[2]),
+        }
+    }
+
+    hash
+}
+
+// what `Cache::normalize` needs to know about a subterm
+#[derive(Clone, Copy)]
+struct Subterm {
+    // the number of nodes
+    size: usize,
+    // the greatest index of a free variable as seen from outside of the subterm; 0 if it's closed
+    free: usize,
+    is_normal: bool,
+}
+
+// describes all the subterms of a term, in preorder, in a single bottom-up pass
+fn subterms(term: &Term) -> Vec<Subterm> {
+    let terms = preorder(term);
+
+    let mut subterms = vec![
+        Subterm {
+            size: 1,
+            free: 0,
+            is_normal: true
+        };
+        terms.len()
+    ];
+    // the subterms of a term follow it, so they are described before it
+    for (position, term) in terms.into_iter().enumerate().rev() {
+        subterms[position] = match term {
+            Var(i) => Subterm {
+                size: 1,
+                free: *i,
+                is_normal: true,
+            },
+            Abs(_) => {
+                let body = subterms[position + 1];
+                Subterm {
+                    size: body.size + 1,
+                    free: body.free.saturating_sub(1),
+                    is_normal: body.is_normal,
+                }
+            }
+            App(boxed) => {
+                let lhs = subterms[position + 1];
+                let rhs = subterms[position + 1 + lhs.size];
+                Subterm {
+                    size: lhs.size + rhs.size + 1,
+                    free: lhs.free.max(rhs.free),
+                    is_normal: lhs.is_normal && rhs.is_normal && !matches!(boxed.0, Abs(_)),
+                }
+            }
+        };
+    }
+
+    subterms
+}
+
+// the number of nodes on the longest path from the root of a term to one of its variables
+fn depth(term: &Term) -> usize {
+    let mut max = 0;
+    let mut stack = vec![(term, 1)];
+
+    while let Some((term, depth)) = stack.pop() {
+        match term {
+            Var(_) => max = max.max(depth),
+            Abs(t) => stack.push((t, depth + 1)),
+            App(boxed) => {
+                stack.push((&boxed.0, depth + 1));
+                stack.push((&boxed.1, depth + 1));
+            }
+        }
+    }
+
+    max
+}
+
+pub(crate) fn is_normal(term: &Term) -> bool {
+    let mut stack = vec![term];
+
+    while let Some(term) = stack.pop() {
+        match term {
+            Var(_) => {}
+            Abs(t) => stack.push(t),
+            App(boxed) => {
+                if let Abs(_) = boxed.0 {
+                    return false;
+                }
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
+        }
+    }
+
+    true
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambda_calculus::combinators::{I, K, Y};
+    use lambda_calculus::data::num::church::{add, mul};
+    use std::{env, thread};
+
+    #[test]
+    fn stable_hash() {
+        assert_eq!(hash(&Var(1)), 0xc709bb3119a0df9e);
+        assert_eq!(hash(&abs(Var(1))), 0x7028add1a8961765);
+    }
+
+    #[test]
+    fn no_normal_form() {
+        let cache = Cache::new().limit(100);
+
+        assert_eq!(cache.normal_form(&app(Y(), I())), None);
+        assert_eq!(cache.normal_form(&abs(app(I(), Var(2)))), None); // not closed
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn normalizing_subterms() {
+        let cache = Cache::new().min_size(1).limit(100);
+        let mut term = abs(app!(
+            Var(1),
+            app!(add(), 1.into_church(), 2.into_church()),
+            app(Y(), I())
+        ));
+        cache.normalize(&mut term);
+
+        assert_eq!(term, abs(app!(Var(1), 3.into_church(), app(Y(), I()))));
+    }
+
+    #[test]
+    fn exhausted_budget() {
+        let sum = app!(add(), 1.into_church(), 2.into_church());
+        let product = app!(mul(), 2.into_church(), 3.into_church());
+        let exact = Cache::new().min_size(1).normalize(&mut sum.clone());
+
+        // the first subterm takes the whole budget; the second one must not get an unlimited one
+        let cache = Cache::new().min_size(1);
+        let mut term = app!(Var(1), sum, product.clone());
+        assert_eq!(cache.normalize_within(&mut term, exact), exact);
+        assert_eq!(term, app!(Var(1), 3.into_church(), product));
+    }
+
+    #[test]
+    fn deep_subterms() {
+        // `Y K` keeps growing: K (K (K ..))
+        let cache = Cache::new();
+
+        assert_eq!(cache.normal_form(&app(Y(), K())), None);
+    }
+
+    #[test]
+    fn divergent_subterms() {
+        // each reduction of `Ω` takes a stack frame
+        let normalize = || {
+            let cache = Cache::new().min_size(1).limit(0);
+            let omega = app(abs(app(Var(1), Var(1))), abs(app(Var(1), Var(1))));
+            let mut term = abs(app!(Var(1), app(K(), I()), omega.clone()));
+            cache.normalize(&mut term);
+
+            assert_eq!(term, abs(app!(Var(1), abs!(2, Var(1)), omega)));
+            assert_eq!(cache.len(), 1); // the normal form of `K I`
+        };
+
+        thread::Builder::new()
+            .stack_size(256 * 1024 * 1024)
+            .spawn(normalize)
+            .unwrap()
+            .join()
+            .unwrap();
+    }
+
+    #[test]
+    fn persistence() {
+        let dir = env::temp_dir().join(format!("blc-cache-{}", std::process::id()));
+        let term = app!(mul(), 3.into_church(), 4.into_church());
+
+        let cache = Cache::with_directory(&dir).unwrap();
+        assert_eq!(cache.normal_form(&term), Some(12.into_church()));
+
+        let cache = Cache::with_directory(&dir).unwrap().limit(1);
+        assert_eq!(cache.normal_form(&term), Some(12.into_church()));
+
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
@@ -0,0 +1,241 @@
+//! Interactive BLC execution
+//!
+//! An interactive program doesn't reduce to its output; instead it reduces to a *request*, which
+//! the runtime services before feeding the reply (if any) back to the program. Requests are
+//! Scott-encoded alternatives carrying a continuation:
+//!
+//! | request     | lambda  | meaning                                                  |
+//! |-------------|---------|----------------------------------------------------------|
+//! | read k      | λλλλ4k  | read a byte `b` from the input and continue with `k b`   |
+//! | write b k   | λλλλ3bk | write the byte `b` to the output and continue with `k`   |
+//! | error b k   | λλλλ2bk | write the byte `b` to the error output and continue with `k` |
+//! | exit c      | λλλλ1c  | stop the program with the exit code `c`                  |
+//!
+//! Bytes are lambda-encoded the same way as by `encoding::lambda::encode_byte`; when the input is
+//! exhausted, `read` replies with an empty list (`λλ1`) instead of a byte.
+//!
+//! Requests are only reduced to their head normal form, so continuations can be (and usually are)
+//! recursive without preventing the program from making progress.
+
+use crate::encoding::binary::from_bits;
+use crate::encoding::lambda::{decode_byte, encode_byte};
+use crate::execution::Error::{self, *};
+use crate::execution::{Options, reduce_head_within, reduce_within, shift_down, shift_up};
+use crate::pair_list::is_list;
+use lambda_calculus::data::boolean::fls;
+use lambda_calculus::*;
+use std::io::{self, Read, Write};
+
+/// The number of alternatives of a request.
+const ALTERNATIVES: usize = 4;
+
+enum Request {
+    Read(Term),
+    Write(Term, Term),
+    Error(Term, Term),
+    Exit(Term),
+}
+
+/// Executes an interactive binary lambda calculus program, servicing its requests with the given
+/// input, output and error output. Returns the program's exit code.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::to_bits;
+/// use blc::encoding::lambda::encode_byte;
+/// use blc::execution::effect::{exit, read, run_interactive, write};
+/// use lambda_calculus::*;
+///
+/// // read a byte, write it back twice and exit with code 0
+/// let double = read(abs(write(Var(1), write(Var(1), exit(encode_byte(0))))));
+///
+/// let mut output = Vec::new();
+/// let code = run_interactive(&to_bits(&double), &b"x"[..], &mut output, Vec::new());
+///
+/// assert_eq!(code, Ok(0));
+/// assert_eq!(output, b"xx");
+/// ```
+pub fn run_interactive<R: Read, W: Write, E: Write>(
+    blc_program: &[u8],
+    input: R,
+    output: W,
+    error: E,
+) -> Result<u8, Error> {
+    run_interactive_with(blc_program, input, output, error, &Options::default())
+}
+
+/// Executes an interactive binary lambda calculus program like `run_interactive`, but with the
+/// given `Options` (except for `encoding`, as requests have their own encoding of bytes). The
+/// limit applies to all the reductions performed during the run, as a program may never exit.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::to_bits;
+/// use blc::encoding::lambda::encode_byte;
+/// use blc::execution::effect::{run_interactive_with, write};
+/// use blc::execution::{Error, Options};
+/// use lambda_calculus::combinators::Y;
+/// use lambda_calculus::*;
+///
+/// // Y (write 'y') - writes `y`s forever
+/// let yes = app(Y(), abs(write(encode_byte(b'y'), Var(1))));
+/// let options = Options { limit: 1000, ..Options::default() };
+///
+/// assert_eq!(
+///     run_interactive_with(&to_bits(&yes), &b""[..], Vec::new(), Vec::new(), &options),
+///     Err(Error::LimitExceeded(1000))
+/// );
+/// ```
+pub fn run_interactive_with<R: Read, W: Write, E: Write>(
+    blc_program: &[u8],
+    mut input: R,
+    mut output: W,
+    mut error: E,
+    options: &Options,
+) -> Result<u8, Error> {
+    let mut term = from_bits(blc_program).map_err(InvalidProgram)?;
+    if let Some(cache) = options.cache {
+        cache.normalize(&mut term);
+    }
+    let mut spent = 0;
+
+    loop {
+        reduce_head_within(&mut term, options, &mut spent)?;
+
+        match request(term)? {
+            Request::Read(k) => {
+                output.flush().map_err(io_error)?; // the program may be waiting for a prompt
+                let reply = match read_byte(&mut input)? {
+                    Some(byte) => encode_byte(byte),
+                    None => fls(),
+                };
+                term = app(k, reply);
+            }
+            Request::Write(b, k) => {
+                output
+                    .write_all(&[byte(b, options, &mut spent)?])
+                    .map_err(io_error)?;
+                term = k;
+            }
+            Request::Error(b, k) => {
+                error
+                    .write_all(&[byte(b, options, &mut spent)?])
+                    .map_err(io_error)?;
+                term = k;
+            }
+            Request::Exit(c) => {
+                output.flush().map_err(io_error)?;
+                error.flush().map_err(io_error)?;
+                return byte(c, options, &mut spent);
+            }
+        }
+    }
+}
+
+/// Produces a request to read a byte and pass it to the continuation `k`.
+pub fn read(k: Term) -> Term {
+    request_term(ALTERNATIVES, vec![k])
+}
+
+/// Produces a request to write the lambda-encoded `byte` to the output and continue with `k`.
+pub fn write(byte: Term, k: Term) -> Term {
+    request_term(ALTERNATIVES - 1, vec![byte, k])
+}
+
+/// Produces a request to write the lambda-encoded `byte` to the error output and continue with
+/// `k`.
+pub fn write_error(byte: Term, k: Term) -> Term {
+    request_term(ALTERNATIVES - 2, vec![byte, k])
+}
+
+/// Produces a request to stop the program with the lambda-encoded exit `code`.
+pub fn exit(code: Term) -> Term {
+    request_term(ALTERNATIVES - 3, vec![code])
+}
+
+fn request_term(alternative: usize, args: Vec<Term>) -> Term {
+    let body = args.into_iter().fold(Var(alternative), |acc, mut arg| {
+        shift_up(&mut arg, ALTERNATIVES, 0);
+        app(acc, arg)
+    });
+
+    abs!(ALTERNATIVES, body)
+}
+
+fn request(term: Term) -> Result<Request, Error> {
+    if !is_request(&term) {
+        return Err(InvalidOutput(term));
+    }
+
+    let mut body = term;
+    for _ in 0..ALTERNATIVES {
+        body = body.unabs().unwrap(); // safe
+    }
+
+    let mut args = Vec::new();
+    while let App(_) = body {
+        let (lhs, mut rhs) = body.unapp().unwrap(); // safe
+        if !shift_down(&mut rhs, ALTERNATIVES) {
+            return Err(InvalidOutput(rhs));
+        }
+        args.push(rhs);
+        body = lhs;
+    }
+
+    match (body, args.pop(), args.pop()) {
+        (Var(4), Some(k), None) => Ok(Request::Read(k)),
+        (Var(3), Some(b), Some(k)) => Ok(Request::Write(b, k)),
+        (Var(2), Some(b), Some(k)) => Ok(Request::Error(b, k)),
+        (Var(1), Some(c), None) => Ok(Request::Exit(c)),
+        _ => unreachable!(),
+    }
+}
+
+// checks whether the term has the form of one of the requests, disregarding their arguments
+fn is_request(term: &Term) -> bool {
+    let mut body = term;
+    for _ in 0..ALTERNATIVES {
+        match body {
+            Abs(t) => body = t,
+            _ => return false,
+        }
+    }
+
+    let mut arity = 0;
+    while let App(boxed) = body {
+        arity += 1;
+        body = &boxed.0;
+    }
+
+    matches!(
+        (body, arity),
+        (Var(4), 1) | (Var(3), 2) | (Var(2), 2) | (Var(1), 1)
+    )
+}
+
+fn byte(mut term: Term, options: &Options, spent: &mut usize) -> Result<u8, Error> {
+    reduce_within(&mut term, options, spent)?;
+
+    if is_list(&term) {
+        Ok(decode_byte(term)?)
+    } else {
+        Err(InvalidOutput(term))
+    }
+}
+
+fn read_byte<R: Read>(input: &mut R) -> Result<Option<u8>, Error> {
+    let mut buf = [0];
+
+    loop {
+        match input.read(&mut buf) {
+            Ok(0) => return Ok(None),
+            Ok(_) => return Ok(Some(buf[0])),
+            Err(ref e) if e.kind() == io::ErrorKind::Interrupted => {}
+            Err(e) => return Err(io_error(e)),
+        }
+    }
+}
+
+fn io_error(error: io::Error) -> Error {
+    Io(error.kind())
+}
@@ -0,0 +1,267 @@
+//! [Lazy K](https://tromp.github.io/cl/lazy-k.html)-compatible I/O
+//!
+//! Lazy K programs consume their input as a list of Church numerals - one for every byte - followed
+//! by an endless stream of 256s, and produce their output in the same form: the output ends with
+//! the first element that is equal to or greater than 256. Lists are the same single-pair lists
+//! (`λ1 head tail`) that are used for byte input.
+//!
+//! Since both the input and the output can be infinite, the output is only ever reduced as far as
+//! it is needed to obtain its next element.
+//!
+//! Lazy K source code can be turned into a lambda term with `parse`, which supports two of the
+//! language's syntaxes: the combinator calculus one (`S(KI)K`, where adjacent terms are applied
+//! to each other) and the Unlambda one (`` `sk ``, where a backtick applies the two terms that
+//! follow it); the Iota and Jot syntaxes aren't supported.
+
+use crate::execution::Error::{self, *};
+use crate::execution::{Options, reduce_head_within, reduce_within, shift_down};
+use lambda_calculus::combinators::{I, K, S, Y};
+use lambda_calculus::data::boolean::{fls, tru};
+use lambda_calculus::*;
+use std::{error, fmt, mem};
+
+/// The Church numeral marking the end of Lazy K input and output.
+const EOF: usize = 256;
+
+/// An error that can occur when parsing Lazy K source code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError {
+    /// the source ended before the program was complete
+    UnexpectedEof,
+    /// a character that isn't part of the supported syntaxes
+    InvalidCharacter {
+        /// the byte offset of the character in the source
+        position: usize,
+        /// the character
+        found: char,
+    },
+    /// a closing parenthesis without an opening one
+    UnbalancedParenthesis {
+        /// the byte offset of the parenthesis in the source
+        position: usize,
+    },
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnexpectedEof => write!(f, "unexpected EOF"),
+            ParseError::InvalidCharacter { position, found } => {
+                write!(f, "invalid character {:?} at byte {}", found, position)
+            }
+            ParseError::UnbalancedParenthesis { position } => {
+                write!(f, "unbalanced parenthesis at byte {}", position)
+            }
+        }
+    }
+}
+
+impl error::Error for ParseError {}
+
+/// Parse Lazy K source code written in the combinator calculus or Unlambda syntax; `#` starts a
+/// comment that lasts until the end of the line. The empty program is the identity.
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::to_bits;
+/// use blc::execution::lazy_k::parse;
+/// use blc::execution::{run, Input};
+///
+/// // the identity, written in both syntaxes
+/// let program = parse("S K # the argument of K doesn't matter\n `kk").unwrap();
+///
+/// assert_eq!(run(&to_bits(&program), Input::LazyK(b"herp")), Ok("herp".into()));
+/// ```
+pub fn parse(source: &str) -> Result<Term, ParseError> {
+    enum Item {
+        Term(Term),
+        Paren,
+        Backtick,
+    }
+
+    // applies the two terms at the top of the stack to each other (consuming a preceding
+    // backtick) until it no longer ends with two terms
+    fn push(stack: &mut Vec<Item>, term: Term) {
+        stack.push(Item::Term(term));
+        while let [.., Item::Term(_), Item::Term(_)] = &stack[..] {
+            let (Some(Item::Term(rhs)), Some(Item::Term(lhs))) = (stack.pop(), stack.pop()) else {
+                unreachable!()
+            };
+            if let Some(Item::Backtick) = stack.last() {
+                stack.pop();
+            }
+            stack.push(Item::Term(app(lhs, rhs)));
+        }
+    }
+
+    let mut stack = Vec::new();
+    let mut comment = false;
+
+    for (position, c) in source.char_indices() {
+        match c {
+            '\n' => comment = false,
+            _ if comment => {}
+            '#' => comment = true,
+            'S' | 's' => push(&mut stack, S()),
+            'K' | 'k' => push(&mut stack, K()),
+            'I' | 'i' => push(&mut stack, I()),
+            '`' => stack.push(Item::Backtick),
+            '(' => stack.push(Item::Paren),
+            ')' if !stack.iter().any(|item| matches!(item, Item::Paren)) => {
+                return Err(ParseError::UnbalancedParenthesis { position });
+            }
+            ')' => match stack.pop() {
+                Some(Item::Paren) => push(&mut stack, I()), // an empty program
+                Some(Item::Term(term)) if matches!(stack.last(), Some(Item::Paren)) => {
+                    stack.pop();
+                    push(&mut stack, term);
+                }
+                // a backtick is still waiting for a term
+                _ => return Err(ParseError::InvalidCharacter { position, found: c }),
+            },
+            c if c.is_whitespace() => {}
+            found => return Err(ParseError::InvalidCharacter { position, found }),
+        }
+    }
+
+    match stack.pop() {
+        None => Ok(I()),
+        Some(Item::Term(program)) if stack.is_empty() => Ok(program),
+        _ => Err(ParseError::UnexpectedEof),
+    }
+}
+
+/// Encode bytes as a Lazy K input `Term`.
+///
+/// # Example
+/// ```
+/// use blc::execution::lazy_k::encode;
+/// use lambda_calculus::data::list::pair::{head, tail};
+/// use lambda_calculus::*;
+///
+/// let input = encode(b"a");
+///
+/// assert_eq!(beta(app(head(), input.clone()), NOR, 0), 97.into_church());
+/// assert_eq!(beta(app(head(), app(tail(), input)), NOR, 0), 256.into_church());
+/// ```
+pub fn encode(input: &[u8]) -> Term {
+    // Y (λl. cons 256 l)
+    let eof = app(Y(), abs!(2, app!(Var(1), EOF.into_church(), Var(2))));
+
+    input.iter().rev().fold(eof, |acc, &byte| {
+        abs(app!(Var(1), (byte as usize).into_church(), acc))
+    })
+}
+
+/// Decode Lazy K output, reducing the `Term` only as much as is necessary to obtain it; the
+/// reductions of all the output count towards the limit from the `Options`, as it may never end.
+///
+/// # Example
+/// ```
+/// use blc::execution::lazy_k::{decode, encode};
+/// use blc::execution::Options;
+///
+/// assert_eq!(decode(encode(b"herp"), &Options::default()), Ok(b"herp".to_vec()));
+/// ```
+pub fn decode(mut term: Term, options: &Options) -> Result<Vec<u8>, Error> {
+    let mut output = Vec::new();
+    let mut spent = 0;
+
+    loop {
+        reduce_head_within(&mut term, options, &mut spent)?;
+        let (head, tail) = uncons(term)?;
+
+        let byte = numeral(head, options, &mut spent)?;
+        if byte >= EOF {
+            return Ok(output);
+        }
+        output.push(byte as u8);
+        term = tail;
+    }
+}
+
+// the head and tail of a list in head normal form, `λ1 head tail`; if they refer to the list's
+// variable (e.g. `λ1 (K n 1) tail`), they are given the selector it stands for
+fn uncons(mut term: Term) -> Result<(Term, Term), Error> {
+    if let Abs(body) = &mut term
+        && let App(boxed) = &mut **body
+        && let App(inner) = &mut boxed.0
+        && inner.0 == Var(1)
+    {
+        let head = mem::replace(&mut inner.1, Var(1));
+        let tail = mem::replace(&mut boxed.1, Var(1));
+
+        return Ok((select(head, tru()), select(tail, fls())));
+    }
+
+    Err(InvalidOutput(term))
+}
+
+fn select(mut term: Term, selector: Term) -> Term {
+    if shift_down(&mut term, 1) {
+        term
+    } else {
+        app(abs(term), selector)
+    }
+}
+
+// Church numerals are applied to free variables in order to also recognize their η-reduced forms
+fn numeral(term: Term, options: &Options, spent: &mut usize) -> Result<usize, Error> {
+    let mut applied = app!(term.clone(), Var(2), Var(1));
+    reduce_within(&mut applied, options, spent)?;
+
+    let mut n = 0;
+    loop {
+        match applied {
+            Var(1) => return Ok(n),
+            App(boxed) if boxed.0 == Var(2) => {
+                n += 1;
+                applied = boxed.1;
+            }
+            _ => return Err(InvalidOutput(term)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn parsing() {
+        let skk = app!(S(), K(), K());
+
+        assert_eq!(parse(""), Ok(I()));
+        assert_eq!(parse("()"), Ok(I()));
+        assert_eq!(parse("SKK"), Ok(skk.clone()));
+        assert_eq!(parse("``skk"), Ok(skk.clone()));
+        assert_eq!(parse("(S)(K\n)K # S K K"), Ok(skk.clone()));
+        assert_eq!(parse("S(KK)"), Ok(app(S(), app(K(), K()))));
+        assert_eq!(parse("`s`kk"), Ok(app(S(), app(K(), K()))));
+        assert_eq!(parse("S`kk"), Ok(app(S(), app(K(), K()))));
+    }
+
+    #[test]
+    fn parsing_errors() {
+        assert_eq!(parse("`s"), Err(ParseError::UnexpectedEof));
+        assert_eq!(parse("(SK"), Err(ParseError::UnexpectedEof));
+        assert_eq!(
+            parse("SK)"),
+            Err(ParseError::UnbalancedParenthesis { position: 2 })
+        );
+        assert_eq!(
+            parse("(`k)"),
+            Err(ParseError::InvalidCharacter {
+                position: 3,
+                found: ')'
+            })
+        );
+        assert_eq!(
+            parse("S*K"),
+            Err(ParseError::InvalidCharacter {
+                position: 1,
+                found: '*'
+            })
+        );
+    }
+}
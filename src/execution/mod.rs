@@ -0,0 +1,528 @@
+//! Binary lambda calculus execution
+//!
+//! A program is applied to its lambda-encoded input (if there is any) and the normal form of that
+//! application is decoded as its output.
+//!
+//! Programs that also take arguments (see `run_with_args`) are applied to two terms instead: first
+//! to a list of the arguments and then to the input, i.e. `program args input`. The arguments are
+//! lambda-encoded with `encoding::lambda::encode_args`, which means that `args` is a single-pair
+//! list (`λ1 head tail`, terminated by the empty list `λλ1`) whose elements are byte strings
+//! encoded the same way as byte input.
+//!
+//! Byte input, arguments and output use the encoding described in `encoding::lambda` unless
+//! `Options::encoding` selects another one (see `encoding::scheme`).
+
+use self::Error::*;
+use self::cache::Cache;
+use self::cache::is_normal;
+use crate::encoding::bcl;
+use crate::encoding::binary::{self, from_bits};
+use crate::encoding::lambda::{self, LambdaDecode, LambdaEncode};
+use crate::encoding::scheme::{Encoding, Standard};
+use lambda_calculus::*;
+use std::{error, fmt, io};
+
+pub mod arithmetic;
+pub mod cache;
+pub mod effect;
+pub mod lazy_k;
+pub mod pipeline;
+
+/// An error that can occur during BLC execution.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Error {
+    /// invalid BLC program
+    InvalidProgram(binary::Error),
+    /// invalid BLC argument
+    InvalidArgument(binary::Error),
+    /// invalid BCL program
+    InvalidBclProgram(bcl::Error),
+    /// a kind of input that the operation doesn't support
+    UnsupportedInput,
+    /// a program with free variables where a closed one is required
+    OpenProgram,
+    /// output that doesn't have the expected form; contains the offending subterm
+    InvalidOutput(Term),
+    /// the normal form wasn't reached within the given number of reductions
+    LimitExceeded(usize),
+    /// an I/O error during interactive execution
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            InvalidProgram(e) => write!(f, "invalid program: {}", e),
+            InvalidArgument(e) => write!(f, "invalid argument: {}", e),
+            InvalidBclProgram(e) => write!(f, "invalid BCL program: {}", e),
+            UnsupportedInput => write!(f, "unsupported kind of input"),
+            OpenProgram => write!(f, "the program is not a closed term"),
+            InvalidOutput(term) => write!(f, "invalid output: {:?}", term),
+            LimitExceeded(limit) => write!(f, "no normal form within {} reductions", limit),
+            Io(kind) => write!(f, "I/O error: {}", io::Error::from(*kind)),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            InvalidProgram(e) | InvalidArgument(e) => Some(e),
+            InvalidBclProgram(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<lambda::Error> for Error {
+    fn from(error: lambda::Error) -> Self {
+        match error {
+            lambda::Error::NotAByte(term) | lambda::Error::Unexpected(_, term) => {
+                InvalidOutput(term)
+            }
+        }
+    }
+}
+
+/// The type of input for BLC execution.
+pub enum Input<'a> {
+    /// no input parameter
+    Nothing,
+    /// BLC input
+    Bits(&'a [u8]),
+    /// unencoded byte input
+    Bytes(&'a [u8]),
+    /// unencoded byte input, passed to the program and expected back from it in the form used by
+    /// Lazy K (see the `lazy_k` module)
+    LazyK(&'a [u8]),
+}
+
+/// Options for BLC execution.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Options<'a> {
+    /// compute Church numeral arithmetic natively (see the `arithmetic` module)
+    pub arithmetic: bool,
+    /// substitute cached normal forms of closed subterms of the program (see the `cache` module)
+    pub cache: Option<&'a Cache>,
+    /// the maximum number of reductions (`0` means no limit)
+    pub limit: usize,
+    /// the encoding of byte input, arguments and output (`encoding::scheme::Standard` by default)
+    pub encoding: Option<&'a dyn Encoding>,
+}
+
+/// Executes a binary lambda calculus program, optionally feeding it the given argument.
+/// More programs can be found in the `tests` directory.
+///
+/// # Example
+/// ```
+/// use blc::execution::run;
+/// use blc::execution::Input::Bytes;
+///
+/// let reverse_blc = b"0001011001000110100000000001011100111110111100001011011110110000010";
+///
+/// assert_eq!(run(&*reverse_blc, Bytes(b"herp derp")), Ok("pred preh".into()));
+/// ```
+pub fn run(blc_program: &[u8], input: Input) -> Result<String, Error> {
+    run_with(blc_program, input, &Options::default())
+}
+
+/// Executes a binary lambda calculus program like `run`, but with the given `Options`.
+///
+/// # Example
+/// ```
+/// use blc::execution::{run_with, Options};
+/// use blc::execution::Input::Bits;
+/// use blc::encoding::binary::to_bits;
+/// use lambda_calculus::data::num::church::pow;
+/// use lambda_calculus::*;
+///
+/// let options = Options { arithmetic: true, ..Options::default() };
+/// let pow_blc = to_bits(&app(pow(), 2.into_church()));
+///
+/// assert_eq!(
+///     run_with(&pow_blc, Bits(&to_bits(&3.into_church())), &options),
+///     Ok(format!("({:?})", 8.into_church()))
+/// );
+/// ```
+pub fn run_with(blc_program: &[u8], input: Input, options: &Options) -> Result<String, Error> {
+    let program = from_bits(blc_program).map_err(InvalidProgram)?;
+
+    evaluate(program, input, options)
+}
+
+/// Executes a binary lambda calculus program that takes a list of byte-string arguments before its
+/// input, as described in the module-level documentation.
+///
+/// # Example
+/// ```
+/// use blc::execution::{run_with_args, Options};
+/// use blc::execution::Input::Bytes;
+///
+/// // λλ. 2 (λλ2) - returns the first argument
+/// let first_arg_blc = b"0000011100000110";
+///
+/// assert_eq!(
+///     run_with_args(first_arg_blc, &[b"herp", b"derp"], Bytes(b"input"), &Options::default()),
+///     Ok("herp".into())
+/// );
+/// ```
+pub fn run_with_args(
+    blc_program: &[u8],
+    args: &[&[u8]],
+    input: Input,
+    options: &Options,
+) -> Result<String, Error> {
+    let program = from_bits(blc_program).map_err(InvalidProgram)?;
+
+    let args = options.encoding.unwrap_or(&Standard).encode_args(args);
+
+    evaluate(app(program, args), input, options)
+}
+
+/// Executes a binary lambda calculus program, applying it to a lambda-encoded Rust value and
+/// decoding its output as another one (see the `encoding::lambda` module for the encodings).
+///
+/// # Example
+/// ```
+/// use blc::encoding::binary::to_bits;
+/// use blc::execution::run_typed;
+/// use lambda_calculus::data::list::pair::map;
+/// use lambda_calculus::*;
+///
+/// // λ. 1 (λλ. map (λ. 1 3 0) 1) - replaces `true`s with the number and `false`s with 0
+/// let program = abs(app(
+///     Var(1),
+///     abs!(2, app!(map(), abs(app!(Var(1), Var(3), 0.into_church())), Var(1)))
+/// ));
+///
+/// assert_eq!(
+///     run_typed::<(u32, Vec<bool>), Vec<u32>>(&to_bits(&program), &(3, vec![true, false, true])),
+///     Ok(vec![3, 0, 3])
+/// );
+/// ```
+pub fn run_typed<A: LambdaEncode, R: LambdaDecode>(
+    blc_program: &[u8],
+    argument: &A,
+) -> Result<R, Error> {
+    run_typed_with(blc_program, argument, &Options::default())
+}
+
+/// Executes a binary lambda calculus program like `run_typed`, but with the given `Options`.
+pub fn run_typed_with<A: LambdaEncode, R: LambdaDecode>(
+    blc_program: &[u8],
+    argument: &A,
+    options: &Options,
+) -> Result<R, Error> {
+    let program = from_bits(blc_program).map_err(InvalidProgram)?;
+    let argument = argument.lambda_encode();
+    let argument_size = |argument: &Term| crate::encoding::binary::to_bits(argument).len();
+
+    run_program(
+        program,
+        argument,
+        argument_size,
+        options,
+        |program, argument| {
+            let mut calculation = app(program, argument);
+            reduce(&mut calculation, options)?;
+
+            Ok(R::lambda_decode(calculation)?)
+        },
+    )
+}
+
+/// Executes a binary combinatory logic program (see the `encoding::bcl` module) like `run_with`;
+/// it is converted to a lambda term and then treated like a BLC program.
+///
+/// # Example
+/// ```
+/// use blc::encoding::bcl::{from_lambda, to_bits};
+/// use blc::execution::{run_bcl, Options};
+/// use blc::execution::Input::Bytes;
+/// use blc::from_bits;
+///
+/// let reverse_blc = b"0001011001000110100000000001011100111110111100001011011110110000010";
+/// let reverse_bcl = to_bits(&from_lambda(&from_bits(reverse_blc).unwrap()).unwrap());
+///
+/// assert_eq!(
+///     run_bcl(&reverse_bcl, Bytes(b"herp derp"), &Options::default()),
+///     Ok("pred preh".into())
+/// );
+/// ```
+pub fn run_bcl(bcl_program: &[u8], input: Input, options: &Options) -> Result<String, Error> {
+    let program = bcl::from_bits(bcl_program).map_err(InvalidBclProgram)?;
+
+    evaluate(bcl::to_lambda(&program), input, options)
+}
+
+// applies the program to its input, reduces the application and decodes the result
+pub(crate) fn evaluate(program: Term, input: Input, options: &Options) -> Result<String, Error> {
+    run_program(program, input, input_size, options, |program, input| {
+        let encoding = options.encoding.unwrap_or(&Standard);
+        let is_lazy_k = matches!(input, Input::LazyK(_));
+        let mut calculation = apply(program, input, encoding)?;
+
+        if is_lazy_k {
+            lazy_k::decode(calculation, options)
+                .map(|bytes| bytes.into_iter().map(char::from).collect())
+        } else {
+            reduce(&mut calculation, options)?;
+            Ok(encoding.decode(calculation)?)
+        }
+    })
+}
+
+// normalizes the program's cached subterms and runs it on its input with `run`, within a tracing
+// span of the whole run; the size of the input is only computed when it is traced
+#[cfg_attr(not(feature = "tracing"), allow(unused_variables))]
+fn run_program<I, T>(
+    mut program: Term,
+    input: I,
+    input_size: impl FnOnce(&I) -> usize,
+    options: &Options,
+    run: impl FnOnce(Term, I) -> Result<T, Error>,
+) -> Result<T, Error> {
+    #[cfg(feature = "tracing")]
+    let _span = tracing::info_span!(
+        "run",
+        program_size = crate::encoding::binary::to_bits(&program).len(),
+        input_size = input_size(&input),
+        strategy = strategy(options),
+    )
+    .entered();
+
+    if let Some(cache) = options.cache {
+        cache.normalize(&mut program);
+    }
+
+    run(program, input)
+}
+
+// applies the program to its (lambda-encoded) input
+fn apply(program: Term, input: Input, encoding: &dyn Encoding) -> Result<Term, Error> {
+    match input {
+        Input::Nothing => Ok(program),
+        Input::Bytes(arg) => Ok(app(program, encoding.encode(arg))),
+        Input::LazyK(arg) => Ok(app(program, lazy_k::encode(arg))),
+        Input::Bits(arg) => Ok(app(program, from_bits(arg).map_err(InvalidArgument)?)),
+    }
+}
+
+// reduces the term to its normal form, returning the number of performed reductions; fails if
+// the normal form isn't reached within the reduction limit
+fn reduce(term: &mut Term, options: &Options) -> Result<usize, Error> {
+    #[cfg(feature = "tracing")]
+    let reductions = {
+        let mut reductions = 0;
+        loop {
+            let interval = match options.limit {
+                0 => PROGRESS_INTERVAL,
+                limit => PROGRESS_INTERVAL.min(limit - reductions),
+            };
+            let count = reduce_limited(term, options, interval);
+            reductions += count;
+            if count < interval || reductions == options.limit {
+                break reductions;
+            }
+            tracing::debug!(reductions, "reduction progress");
+        }
+    };
+
+    #[cfg(not(feature = "tracing"))]
+    let reductions = reduce_limited(term, options, options.limit);
+
+    if options.limit != 0 && reductions == options.limit && !is_normal(term) {
+        Err(LimitExceeded(options.limit))
+    } else {
+        Ok(reductions)
+    }
+}
+
+// reduces the term like `reduce`, but only with the reductions left of the limit after `spent`
+// ones, and adds the ones it performs to them
+pub(crate) fn reduce_within(
+    term: &mut Term,
+    options: &Options,
+    spent: &mut usize,
+) -> Result<(), Error> {
+    let Some(limit) = remaining(options.limit, *spent) else {
+        return if is_normal(term) {
+            Ok(())
+        } else {
+            Err(LimitExceeded(options.limit))
+        };
+    };
+
+    match reduce(term, &Options { limit, ..*options }) {
+        Ok(reductions) => {
+            *spent += reductions;
+            Ok(())
+        }
+        Err(LimitExceeded(_)) => Err(LimitExceeded(options.limit)),
+        Err(e) => Err(e),
+    }
+}
+
+// reduces the term to its head normal form like `reduce_within` does to its normal form
+pub(crate) fn reduce_head_within(
+    term: &mut Term,
+    options: &Options,
+    spent: &mut usize,
+) -> Result<(), Error> {
+    if let Some(limit) = remaining(options.limit, *spent) {
+        *spent += term.reduce(HSP, limit);
+    }
+
+    if options.limit != 0 && *spent >= options.limit && !is_head_normal(term) {
+        Err(LimitExceeded(options.limit))
+    } else {
+        Ok(())
+    }
+}
+
+// the number of reductions left of the limit after `spent` ones (`0` meaning no limit); `None` if
+// there are none
+fn remaining(limit: usize, spent: usize) -> Option<usize> {
+    match limit {
+        0 => Some(0),
+        limit if spent < limit => Some(limit - spent),
+        _ => None,
+    }
+}
+
+fn is_head_normal(mut term: &Term) -> bool {
+    while let Abs(t) = term {
+        term = t;
+    }
+    while let App(boxed) = term {
+        if let Abs(_) = boxed.0 {
+            return false;
+        }
+        term = &boxed.0;
+    }
+
+    true
+}
+
+fn reduce_limited(term: &mut Term, options: &Options, limit: usize) -> usize {
+    if options.arithmetic {
+        arithmetic::reduce(term, limit)
+    } else {
+        term.reduce(NOR, limit)
+    }
+}
+
+/// The number of reductions between reduction progress events.
+#[cfg(feature = "tracing")]
+const PROGRESS_INTERVAL: usize = 1 << 14;
+
+fn input_size(input: &Input) -> usize {
+    match input {
+        Input::Nothing => 0,
+        Input::Bits(arg) | Input::Bytes(arg) | Input::LazyK(arg) => arg.len(),
+    }
+}
+
+#[cfg(feature = "tracing")]
+fn strategy(options: &Options) -> &'static str {
+    if options.arithmetic {
+        "normal, arithmetic"
+    } else {
+        "normal"
+    }
+}
+
+// shifts the free variables of a term up by `by`
+pub(crate) fn shift_up(term: &mut Term, by: usize, depth: usize) {
+    match term {
+        Var(i) if *i > depth => *i += by,
+        Var(_) => {}
+        Abs(t) => shift_up(t, by, depth + 1),
+        App(boxed) => {
+            shift_up(&mut boxed.0, by, depth);
+            shift_up(&mut boxed.1, by, depth);
+        }
+    }
+}
+
+// shifts the free variables of a term down by `by`; returns `false` and leaves the term intact if
+// it refers to any of the `by` innermost variables that are free in it
+pub(crate) fn shift_down(term: &mut Term, by: usize) -> bool {
+    fn refers(term: &Term, by: usize, depth: usize) -> bool {
+        match term {
+            Var(i) => *i > depth && *i <= depth + by,
+            Abs(t) => refers(t, by, depth + 1),
+            App(boxed) => refers(&boxed.0, by, depth) || refers(&boxed.1, by, depth),
+        }
+    }
+
+    fn shift(term: &mut Term, by: usize, depth: usize) {
+        match term {
+            Var(i) if *i > depth => *i -= by,
+            Var(_) => {}
+            Abs(t) => shift(t, by, depth + 1),
+            App(boxed) => {
+                shift(&mut boxed.0, by, depth);
+                shift(&mut boxed.1, by, depth);
+            }
+        }
+    }
+
+    if refers(term, by, 0) {
+        return false;
+    }
+    shift(term, by, 0);
+
+    true
+}
+
+/*
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::encoding::binary::{decompress, to_bits};
+
+    #[test] /* WIP; this one parses properly, but doesn't return the expected result */
+    fn hilbert() {
+        // program code from http://www.ioccc.org/2012/tromp/hilbert.Blc
+        let hilbert_compressed =
+            [0x18, 0x18, 0x18, 0x18, 0x11, 0x11, 0x54, 0x68, 0x06, 0x04, 0x15, 0x5f, 0xf0, 0x41,
+             0x9d, 0xf9, 0xde, 0x16, 0xff, 0xfe, 0x5f, 0x3f, 0xef, 0xf6, 0x15, 0xff, 0x94, 0x68,
+             0x40, 0x58, 0x11, 0x7e, 0x05, 0xcb, 0xfe, 0xbc, 0xbf, 0xee, 0x86, 0xcb, 0x94, 0x68,
+             0x16, 0x00, 0x5c, 0x0b, 0xfa, 0xcb, 0xfb, 0xf7, 0x1a, 0x85, 0xe0, 0x5c, 0xf4, 0x14,
+             0xd5, 0xfe, 0x08, 0x18, 0x0b, 0x04, 0x8d, 0x08, 0x00, 0xe0, 0x78, 0x01, 0x64, 0x45,
+             0xff, 0xe5, 0xff, 0x7f, 0xff, 0xfe, 0x5f, 0xff, 0x2f, 0xc0, 0x2f, 0x7a, 0xd9, 0x7f,
+             0x5b, 0xff, 0xff, 0xfb, 0xff, 0xfc, 0xaa, 0xff, 0xf7, 0x81, 0x7f, 0xfa, 0xdf, 0x76,
+             0x69, 0x54, 0x68, 0x06, 0x01, 0x57, 0xf7, 0xe1, 0x60, 0x5c, 0x13, 0xfe, 0x80, 0xb2,
+             0x2c, 0x18, 0x58, 0x1b, 0xfe, 0x5c, 0x10, 0x42, 0xff, 0x80, 0x5d, 0xee, 0xc0, 0x6c,
+             0x2c, 0x0c, 0x06, 0x08, 0x19, 0x1a, 0x00, 0x16, 0x7f, 0xbc, 0xbc, 0xfd, 0xf6, 0x5f,
+             0x7c, 0x0a, 0x20];
+
+        let hilbert_blc = decompress(&hilbert_compressed);
+        let arg = b"1234";
+
+        assert_eq!(run(&hilbert_blc[..], Bytes(&arg[..])), Ok("WIP".into()));
+    }
+*/
+/*
+    #[test] /* WIP; this just returns λ1 */
+    fn brainfuck() {
+        // program code from http://www.ioccc.org/2012/tromp/bf.Blc
+        let bf_interpreter_compressed =
+            [0x44, 0x51, 0xa1, 0x01, 0x84, 0x55, 0xd5, 0x02, 0xb7, 0x70, 0x30, 0x22, 0xff, 0x32,
+             0xf0, 0x00, 0xbf, 0xf9, 0x85, 0x7f, 0x5e, 0xe1, 0x6f, 0x95, 0x7f, 0x7d, 0xee, 0xc0,
+             0xe5, 0x54, 0x68, 0x00, 0x58, 0x55, 0xfd, 0xfb, 0xe0, 0x45, 0x57, 0xfd, 0xeb, 0xfb,
+             0xf0, 0xb6, 0xf0, 0x2f, 0xd6, 0x07, 0xe1, 0x6f, 0x73, 0xd7, 0xf1, 0x14, 0xbc, 0xc0,
+             0x0b, 0xff, 0x2e, 0x1f, 0xa1, 0x6f, 0x66, 0x17, 0xe5, 0xca, 0x34, 0x20, 0x0a, 0xc8,
+             0xd0, 0x0b, 0x99, 0xee, 0x1f, 0xe5, 0x7f, 0x5a, 0x6a, 0x1f, 0xff, 0x0f, 0xff, 0x87,
+             0x9d, 0x04, 0xd0, 0xab, 0x00,
+             0x05, 0xdb, 0x23, 0x40, 0xb7, 0x3b, 0x28, 0xcc, 0xc0, 0xb0, 0x6c, 0x0e, 0x74, 0x10];
+
+        let bf_interpreter_blc = decompress(&bf_interpreter_compressed);
+        let bf_hello =
+            b"++++++++[>++++[>++>+++>+++>+<<<<-]>+>+>->>+[<]<-]>>.>---.+++++++..+++.>>.<-.<.+++.------.--------.>>+.>++.";
+
+        assert_eq!(run(&bf_interpreter_blc, Bytes(&bf_hello[..])), Ok("Hello World!".into()));
+    }
+}
+*/
@@ -0,0 +1,144 @@
+//! Pipelines of BLC programs
+//!
+//! A pipeline composes its stages into a single program, `λ. pₙ (… (p₂ (p₁ 1)))`, so that the
+//! output of every stage is fed to the next one as a lambda term rather than being decoded and
+//! re-encoded in between. Since the composition is reduced as a whole, the stages are as lazy as
+//! they would be in a single program: a stage can start consuming its input before the previous
+//! stage has produced all of it.
+
+use crate::encoding::binary::{from_bits, to_bits};
+use crate::encoding::scheme::Standard;
+use crate::execution::Error::{self, *};
+use crate::execution::{Input, Options, evaluate, reduce};
+use lambda_calculus::*;
+
+/// A sequence of BLC programs, each consuming the output of the previous one.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Pipeline {
+    stages: Vec<Term>,
+}
+
+/// Statistics of a single pipeline stage.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Stats {
+    /// the number of performed reductions
+    pub reductions: usize,
+    /// the size of the stage's output in BLC bits
+    pub output_size: usize,
+}
+
+impl Pipeline {
+    /// Creates a pipeline consisting of the given BLC programs, in order of execution.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::execution::pipeline::Pipeline;
+    /// use blc::execution::Input::Bytes;
+    ///
+    /// let reverse_blc = b"0001011001000110100000000001011100111110111100001011011110110000010";
+    /// let pipeline = Pipeline::new(&[&reverse_blc[..], &reverse_blc[..]]).unwrap();
+    ///
+    /// assert_eq!(pipeline.run(Bytes(b"herp derp")), Ok("herp derp".into()));
+    /// ```
+    pub fn new(blc_programs: &[&[u8]]) -> Result<Self, Error> {
+        let mut pipeline = Pipeline::default();
+
+        for blc_program in blc_programs {
+            pipeline.push(blc_program)?;
+        }
+
+        Ok(pipeline)
+    }
+
+    /// Appends a BLC program to the end of the pipeline. Programs with free variables can't be
+    /// composed and are rejected.
+    pub fn push(&mut self, blc_program: &[u8]) -> Result<(), Error> {
+        let program = from_bits(blc_program).map_err(InvalidProgram)?;
+        if program.has_free_variables() {
+            return Err(OpenProgram);
+        }
+
+        self.stages.push(program);
+
+        Ok(())
+    }
+
+    /// Returns the number of stages in the pipeline.
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    /// Returns `true` if the pipeline has no stages.
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// Returns the composition of the pipeline's stages as a single lambda `Term`; an empty
+    /// pipeline is the identity function.
+    pub fn term(&self) -> Term {
+        abs(self
+            .stages
+            .iter()
+            .cloned()
+            .fold(Var(1), |acc, stage| app(stage, acc)))
+    }
+
+    /// Returns the composition of the pipeline's stages as a single BLC program.
+    pub fn to_bits(&self) -> Vec<u8> {
+        to_bits(&self.term())
+    }
+
+    /// Executes the pipeline, feeding it the given argument.
+    pub fn run(&self, input: Input) -> Result<String, Error> {
+        self.run_with(input, &Options::default())
+    }
+
+    /// Executes the pipeline like `run`, but with the given `Options`.
+    pub fn run_with(&self, input: Input, options: &Options) -> Result<String, Error> {
+        evaluate(self.term(), input, options)
+    }
+
+    /// Executes the pipeline one stage at a time, reducing the output of each stage to its normal
+    /// form before passing it on, and returns the final output along with the statistics of every
+    /// stage. Unlike `run`, this requires a byte or BLC input and a normal form of every intermediate
+    /// output.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::execution::pipeline::Pipeline;
+    /// use blc::execution::Input::Bytes;
+    /// use blc::execution::Options;
+    ///
+    /// let reverse_blc = b"0001011001000110100000000001011100111110111100001011011110110000010";
+    /// let pipeline = Pipeline::new(&[&reverse_blc[..], &reverse_blc[..]]).unwrap();
+    /// let (output, stats) = pipeline.run_staged(Bytes(b"herp"), &Options::default()).unwrap();
+    ///
+    /// assert_eq!(output, "herp");
+    /// assert_eq!(stats.len(), 2);
+    /// assert_eq!(stats[0].output_size, stats[1].output_size);
+    /// ```
+    pub fn run_staged(
+        &self,
+        input: Input,
+        options: &Options,
+    ) -> Result<(String, Vec<Stats>), Error> {
+        let encoding = options.encoding.unwrap_or(&Standard);
+        let mut calculation = match input {
+            Input::Nothing | Input::LazyK(_) => return Err(UnsupportedInput),
+            Input::Bytes(arg) => encoding.encode(arg),
+            Input::Bits(arg) => from_bits(arg).map_err(InvalidArgument)?,
+        };
+
+        let mut stats = Vec::with_capacity(self.stages.len());
+        for stage in &self.stages {
+            calculation = app(stage.clone(), calculation);
+            let reductions = reduce(&mut calculation, options)?;
+            stats.push(Stats {
+                reductions,
+                output_size: to_bits(&calculation).len(),
+            });
+        }
+
+        Ok((encoding.decode(calculation)?, stats))
+    }
+}
@@ -41,7 +41,17 @@
 
 pub use self::encoding::binary::{from_bits, to_bits};
 pub use self::execution::run;
+/// The implementation of the lambda calculus that this crate's `Term`s come from.
+pub use lambda_calculus;
 
+pub mod analysis;
+pub mod differential;
 pub mod encoding;
 pub mod execution;
 mod pair_list;
+pub mod program;
+#[cfg(feature = "serde")]
+pub mod serialization;
+#[cfg(feature = "store")]
+pub mod store;
+mod subterms;
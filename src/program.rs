@@ -0,0 +1,283 @@
+//! Validated BLC programs
+//!
+//! Programs can be stored in several formats (see `Format`); `Program::from_slice` and
+//! `Program::load` detect the format of their input by parsing it as the first of the following
+//! formats it looks like it's in and succeeds with:
+//!
+//! - data starting with the magic bytes of a container may be a container,
+//! - data consisting only of `0`s, `1`s and the whitespace ignored between bits (spaces, tabs and
+//!   line breaks) may be in the ASCII bit format, holding a single term,
+//! - data consisting only of pairs of hexadecimal digits (optionally prefixed with `0x`) separated
+//!   by whitespace or commas may be a hex dump,
+//! - UTF-8 text containing a `λ` or a `\` may be lambda notation, either the De Bruijn one (e.g.
+//!   `λλ2`) or, if it contains dots, the classic one (e.g. `λx.λy.x`),
+//! - any data may be packed, so that is tried last.
+
+use self::Error::*;
+use crate::analysis::{Analysis, analyze};
+use crate::encoding::binary::{self, WHITESPACE, from_bits, from_bits_strict, from_bytes, to_bits};
+use crate::encoding::container::{self, Container};
+use crate::execution::{self, Input, Options, evaluate};
+use lambda_calculus::parser::ParseError;
+use lambda_calculus::*;
+use std::path::Path;
+use std::{error, fmt, fs, io};
+
+/// An error that can occur when a term is not a valid BLC program or it can't be loaded.
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    /// the program couldn't be parsed
+    Parse(binary::Error),
+    /// the program is not a valid hex dump
+    InvalidHex,
+    /// the program couldn't be parsed as lambda notation
+    InvalidLambda(ParseError),
+    /// the program is not a valid container
+    InvalidContainer(container::Error),
+    /// the program couldn't be read
+    Io(io::ErrorKind),
+    /// the program contains variables with the invalid index 0
+    IllFormed,
+    /// the program has free variables (listed as seen from outside of it)
+    Open(Vec<usize>),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Parse(e) => write!(f, "{}", e),
+            InvalidHex => write!(f, "not a valid hex dump"),
+            InvalidLambda(e) => write!(f, "{}", e),
+            InvalidContainer(e) => write!(f, "{}", e),
+            Io(kind) => write!(f, "I/O error: {}", io::Error::from(*kind)),
+            IllFormed => write!(f, "the program contains variables with the index 0"),
+            Open(free) => write!(f, "the program has free variables: {:?}", free),
+        }
+    }
+}
+
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Parse(e) => Some(e),
+            InvalidLambda(e) => Some(e),
+            InvalidContainer(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+/// A format in which programs can be stored.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    /// one ASCII `0` or `1` per bit (`.blc`)
+    Bits,
+    /// packed bits (`.Blc`)
+    Packed,
+    /// a hex dump of packed bits
+    Hex,
+    /// lambda notation
+    Lambda,
+    /// a container (see `encoding::container`)
+    Container,
+}
+
+impl Format {
+    /// Detects the format of a stored program, parsing it like `Program::from_slice`; if it isn't
+    /// a valid program in any format, the first one it looks like it's in is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::program::Format;
+    ///
+    /// assert_eq!(Format::detect(b"0000110"), Format::Bits);
+    /// assert_eq!(Format::detect(b"0x01, 0xcb, 0xda"), Format::Hex);
+    /// assert_eq!(Format::detect("λλ2".as_bytes()), Format::Lambda);
+    /// assert_eq!(Format::detect(&[0x01, 0xcb, 0xda]), Format::Packed);
+    /// ```
+    pub fn detect(data: &[u8]) -> Format {
+        match Program::from_slice(data) {
+            Ok((_, format)) => format,
+            Err(_) => Format::candidates(data)[0],
+        }
+    }
+
+    // the formats the data looks like it's in, in the order they are tried
+    fn candidates(data: &[u8]) -> Vec<Format> {
+        let mut formats = Vec::new();
+
+        if container::is_container(data) {
+            formats.push(Format::Container);
+        }
+        if data
+            .iter()
+            .all(|b| matches!(b, b'0' | b'1') || WHITESPACE.contains(b))
+            && data.iter().any(|b| !WHITESPACE.contains(b))
+        {
+            formats.push(Format::Bits);
+        }
+        if parse_hex(data).is_some() {
+            formats.push(Format::Hex);
+        }
+        if let Ok(text) = std::str::from_utf8(data)
+            && text.contains(['λ', '\\'])
+        {
+            formats.push(Format::Lambda);
+        }
+        formats.push(Format::Packed); // packed data can look like anything
+
+        formats
+    }
+}
+
+/// A BLC program: a closed lambda term with only valid De Bruijn indices.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Program {
+    term: Term,
+}
+
+impl Program {
+    /// Creates a program from a lambda `Term`, validating it.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::program::{Error, Program};
+    /// use lambda_calculus::*;
+    ///
+    /// assert!(Program::new(abs(Var(1))).is_ok());
+    /// assert_eq!(Program::new(abs(Var(2))), Err(Error::Open(vec![1])));
+    /// assert_eq!(Program::new(abs(Var(0))), Err(Error::IllFormed));
+    /// ```
+    pub fn new(term: Term) -> Result<Self, Error> {
+        let analysis = analyze(&term);
+
+        if !analysis.is_well_formed() {
+            Err(IllFormed)
+        } else if !analysis.is_closed() {
+            Err(Open(analysis.free_variables))
+        } else {
+            Ok(Program { term })
+        }
+    }
+
+    /// Parses and validates a BLC program.
+    pub fn from_bits(blc_program: &[u8]) -> Result<Self, Error> {
+        Program::new(from_bits(blc_program).map_err(Parse)?)
+    }
+
+    /// Parses and validates a program stored in any of the supported formats, returning it along
+    /// with the detected format (see the module documentation); if it isn't a valid program in
+    /// any of them, the error is the one for the first format it looks like it's in.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::program::{Format, Program};
+    ///
+    /// let (k, format) = Program::from_slice(b"00 00 110").unwrap();
+    ///
+    /// assert_eq!(format, Format::Bits);
+    /// assert_eq!(Program::from_slice("λλ2".as_bytes()), Ok((k.clone(), Format::Lambda)));
+    /// assert_eq!(Program::from_slice(b"0c"), Ok((k, Format::Hex)));
+    /// ```
+    pub fn from_slice(data: &[u8]) -> Result<(Self, Format), Error> {
+        let mut formats = Format::candidates(data).into_iter();
+        let first = formats.next().unwrap(); // safe; there's always at least one candidate
+
+        let error = match Program::from_slice_as(data, first) {
+            Ok(program) => return Ok((program, first)),
+            Err(error) => error,
+        };
+        formats
+            .find_map(|format| Some((Program::from_slice_as(data, format).ok()?, format)))
+            .ok_or(error)
+    }
+
+    /// Parses and validates a program stored in the given format.
+    pub fn from_slice_as(data: &[u8], format: Format) -> Result<Self, Error> {
+        let term = match format {
+            Format::Bits => from_bits_strict(data).map_err(Parse)?,
+            Format::Packed => from_bytes(data).map_err(Parse)?,
+            Format::Hex => from_bytes(&parse_hex(data).ok_or(InvalidHex)?).map_err(Parse)?,
+            Format::Lambda => {
+                let text = std::str::from_utf8(data)
+                    .or(Err(InvalidLambda(ParseError::InvalidExpression)))?;
+                let notation = if text.contains('.') {
+                    Classic
+                } else {
+                    DeBruijn
+                };
+                parse(text, notation).map_err(InvalidLambda)?
+            }
+            Format::Container => Container::from_bytes(data).map_err(InvalidContainer)?.term,
+        };
+
+        Program::new(term)
+    }
+
+    /// Loads a program from a file, detecting its format like `from_slice`.
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<(Self, Format), Error> {
+        Program::from_slice(&fs::read(path).map_err(|e| Io(e.kind()))?)
+    }
+
+    /// Loads a program stored in the given format from a file.
+    pub fn load_as<P: AsRef<Path>>(path: P, format: Format) -> Result<Self, Error> {
+        Program::from_slice_as(&fs::read(path).map_err(|e| Io(e.kind()))?, format)
+    }
+
+    /// Returns the program's lambda `Term`.
+    pub fn term(&self) -> &Term {
+        &self.term
+    }
+
+    /// Returns the program's lambda `Term`, consuming the program.
+    pub fn into_term(self) -> Term {
+        self.term
+    }
+
+    /// Returns the program's BLC representation.
+    pub fn to_bits(&self) -> Vec<u8> {
+        to_bits(&self.term)
+    }
+
+    /// Returns the static properties of the program.
+    pub fn analysis(&self) -> Analysis {
+        analyze(&self.term)
+    }
+
+    /// Executes the program like `execution::run_with`.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::execution::Input::Bytes;
+    /// use blc::execution::Options;
+    /// use blc::program::Program;
+    ///
+    /// let reverse_blc = b"0001011001000110100000000001011100111110111100001011011110110000010";
+    /// let reverse = Program::from_bits(reverse_blc).unwrap();
+    ///
+    /// assert_eq!(reverse.run(Bytes(b"herp"), &Options::default()), Ok("preh".into()));
+    /// ```
+    pub fn run(&self, input: Input, options: &Options) -> Result<String, execution::Error> {
+        evaluate(self.term.clone(), input, options)
+    }
+}
+
+// parses pairs of hexadecimal digits, optionally prefixed with `0x` and separated with whitespace
+// or commas
+fn parse_hex(data: &[u8]) -> Option<Vec<u8>> {
+    let text = std::str::from_utf8(data).ok()?;
+    let mut bytes = Vec::new();
+
+    for word in text.split(|c: char| c.is_ascii_whitespace() || c == ',') {
+        let digits = word.strip_prefix("0x").unwrap_or(word);
+        if digits.len() % 2 != 0 || !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        for pair in digits.as_bytes().chunks(2) {
+            let pair = std::str::from_utf8(pair).ok()?;
+            bytes.push(u8::from_str_radix(pair, 16).ok()?);
+        }
+    }
+
+    if bytes.is_empty() { None } else { Some(bytes) }
+}
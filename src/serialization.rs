@@ -0,0 +1,123 @@
+//! `serde` support (requires the `serde` feature)
+//!
+//! `Program`s and `Bits`-wrapped terms are serialized as their compact BLC representation, i.e. a
+//! string of `0`s and `1`s. `Tree`-wrapped terms are serialized as a tree of their constructors
+//! instead; since serializing it is recursive, it is only suitable for terms of moderate depth.
+//!
+//! # Example
+//! ```
+//! use blc::program::Program;
+//! use blc::serialization::Tree;
+//! use lambda_calculus::*;
+//!
+//! let k = Program::from_bits(b"0000110").unwrap();
+//!
+//! assert_eq!(serde_json::to_string(&k).unwrap(), r#""0000110""#);
+//! assert_eq!(
+//!     serde_json::to_string(&Tree(k.into_term())).unwrap(),
+//!     r#"{"Abs":{"Abs":{"Var":2}}}"#
+//! );
+//! assert_eq!(
+//!     serde_json::from_str::<Tree>(r#"{"App":[{"Var":1},{"Var":2}]}"#).unwrap(),
+//!     Tree(app(Var(1), Var(2)))
+//! );
+//! ```
+
+use crate::encoding::binary::{from_bits_strict, to_bits};
+use crate::program::Program;
+use lambda_calculus::*;
+use serde::de::{self, Deserialize, Deserializer};
+use serde::ser::{Serialize, SerializeTupleVariant, Serializer};
+
+/// A lambda `Term` that is serialized as its BLC representation.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Bits(pub Term);
+
+/// A lambda `Term` that is serialized as a tree of its constructors.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tree(pub Term);
+
+// serializes a string of ASCII bits
+fn serialize_bits<S: Serializer>(term: &Term, serializer: S) -> Result<S::Ok, S::Error> {
+    // safe; the bits are ASCII
+    serializer.serialize_str(std::str::from_utf8(&to_bits(term)).unwrap())
+}
+
+fn deserialize_bits<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Term, D::Error> {
+    let bits = String::deserialize(deserializer)?;
+
+    from_bits_strict(bits.as_bytes()).map_err(de::Error::custom)
+}
+
+impl Serialize for Bits {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bits(&self.0, serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bits {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserialize_bits(deserializer).map(Bits)
+    }
+}
+
+impl Serialize for Program {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serialize_bits(self.term(), serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Program {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Program::new(deserialize_bits(deserializer)?).map_err(de::Error::custom)
+    }
+}
+
+// a borrowed subterm of a `Tree`
+struct Subtree<'a>(&'a Term);
+
+impl Serialize for Subtree<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self.0 {
+            Var(i) => serializer.serialize_newtype_variant("Term", 0, "Var", i),
+            Abs(t) => serializer.serialize_newtype_variant("Term", 1, "Abs", &Subtree(t)),
+            App(boxed) => {
+                let mut app = serializer.serialize_tuple_variant("Term", 2, "App", 2)?;
+                app.serialize_field(&Subtree(&boxed.0))?;
+                app.serialize_field(&Subtree(&boxed.1))?;
+                app.end()
+            }
+        }
+    }
+}
+
+// the deserialized form of a `Tree`, mirroring the serialized one
+#[derive(serde::Deserialize)]
+#[serde(rename = "Term")]
+enum Node {
+    Var(usize),
+    Abs(Box<Node>),
+    App(Box<Node>, Box<Node>),
+}
+
+impl From<Node> for Term {
+    fn from(node: Node) -> Term {
+        match node {
+            Node::Var(i) => Var(i),
+            Node::Abs(t) => abs(Term::from(*t)),
+            Node::App(lhs, rhs) => app(Term::from(*lhs), Term::from(*rhs)),
+        }
+    }
+}
+
+impl Serialize for Tree {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        Subtree(&self.0).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tree {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Node::deserialize(deserializer).map(|node| Tree(node.into()))
+    }
+}
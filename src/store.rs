@@ -0,0 +1,405 @@
+//! Content-addressed term storage
+//!
+//! De Bruijn terms are canonical up to α-equivalence, so a structural hash identifies a term. This
+//! module defines a stable one, `hash`, and a `Store` of terms in a directory, keyed by it.
+//!
+//! The hash is computed like a Merkle tree: the hash of a term depends only on its constructor and
+//! the hashes of its immediate subterms. Thanks to that, a stored term can refer to any of its
+//! subterms by hash instead of containing it; when a term is put in a `Store`, all its subterms
+//! that are already stored (e.g. shared library functions) are replaced with such references, and
+//! they are substituted back when it is loaded.
+//!
+//! The store requires the `store` feature.
+
+use self::Error::*;
+use crate::subterms::preorder;
+use lambda_calculus::*;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::{error, fmt, fs, io};
+
+/// The SHA-256-based structural hash of a `Term`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Hash(pub [u8; 32]);
+
+/// An error that can occur when using a `Store`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// a term that isn't in the store
+    NotFound(Hash),
+    /// a stored term that is malformed or doesn't match its hash
+    Corrupt(Hash),
+    /// a string that isn't a hexadecimal hash
+    InvalidHash,
+    /// an I/O error
+    Io(io::ErrorKind),
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            NotFound(hash) => write!(f, "term {} not found", hash),
+            Corrupt(hash) => write!(f, "term {} is corrupt", hash),
+            InvalidHash => write!(f, "invalid hash"),
+            Io(kind) => write!(f, "I/O error: {}", io::Error::from(*kind)),
+        }
+    }
+}
+
+impl error::Error for Error {}
+
+impl From<io::Error> for Error {
+    fn from(error: io::Error) -> Self {
+        Io(error.kind())
+    }
+}
+
+impl fmt::Display for Hash {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for byte in &self.0 {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for Hash {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Error> {
+        if s.len() != 64 || !s.is_ascii() {
+            return Err(InvalidHash);
+        }
+
+        let mut hash = [0; 32];
+        for (byte, digits) in hash.iter_mut().zip(s.as_bytes().chunks(2)) {
+            let digits = std::str::from_utf8(digits).map_err(|_| InvalidHash)?;
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| InvalidHash)?;
+        }
+
+        Ok(Hash(hash))
+    }
+}
+
+// the tags of the constructors in hashed data and in stored terms
+const VAR: u8 = 0;
+const ABS: u8 = 1;
+const APP: u8 = 2;
+const REF: u8 = 3;
+
+/// Computes the structural hash of a `Term`. It is the SHA-256 hash of a tag of the term's
+/// constructor followed by the term's index (as a 64-bit little-endian number) or by the hashes of
+/// its subterms.
+///
+/// # Example
+/// ```
+/// use blc::store::hash;
+/// use lambda_calculus::*;
+///
+/// assert_eq!(hash(&abs(Var(1))), hash(&abs(Var(1))));
+/// assert_ne!(hash(&abs(Var(1))), hash(&abs!(2, Var(1))));
+/// ```
+pub fn hash(term: &Term) -> Hash {
+    Subterms::new(term).hashes[0]
+}
+
+// the subterms of a term in preorder, along with their sizes and hashes
+struct Subterms<'a> {
+    terms: Vec<&'a Term>,
+    sizes: Vec<usize>,
+    hashes: Vec<Hash>,
+}
+
+impl<'a> Subterms<'a> {
+    fn new(term: &'a Term) -> Self {
+        let terms = preorder(term);
+
+        // in reverse preorder, the subterms of a term are always handled before it
+        let mut sizes = vec![1; terms.len()];
+        let mut hashes = vec![Hash([0; 32]); terms.len()];
+        for (i, term) in terms.iter().enumerate().rev() {
+            let mut data = Vec::with_capacity(65);
+            match term {
+                Var(n) => {
+                    data.push(VAR);
+                    data.extend_from_slice(&(*n as u64).to_le_bytes());
+                }
+                Abs(_) => {
+                    sizes[i] += sizes[i + 1];
+                    data.push(ABS);
+                    data.extend_from_slice(&hashes[i + 1].0);
+                }
+                App(_) => {
+                    let rhs = i + 1 + sizes[i + 1];
+                    sizes[i] += sizes[i + 1] + sizes[rhs];
+                    data.push(APP);
+                    data.extend_from_slice(&hashes[i + 1].0);
+                    data.extend_from_slice(&hashes[rhs].0);
+                }
+            }
+            hashes[i] = Hash(Sha256::digest(&data).into());
+        }
+
+        Subterms {
+            terms,
+            sizes,
+            hashes,
+        }
+    }
+}
+
+/// A store of lambda terms in a directory, keyed by their structural hashes.
+#[derive(Debug, Clone)]
+pub struct Store {
+    directory: PathBuf,
+}
+
+impl Store {
+    /// Opens the store in the given directory, creating it if necessary.
+    pub fn open<P: AsRef<Path>>(directory: P) -> io::Result<Self> {
+        fs::create_dir_all(&directory)?;
+
+        Ok(Store {
+            directory: directory.as_ref().to_path_buf(),
+        })
+    }
+
+    /// Returns `true` if the term with the given hash is in the store.
+    pub fn contains(&self, hash: &Hash) -> bool {
+        self.path(hash).exists()
+    }
+
+    /// Puts a `Term` in the store and returns its hash. Its subterms that are already in the
+    /// store are not stored again; they are referred to instead.
+    ///
+    /// # Example
+    /// ```
+    /// use blc::store::Store;
+    /// use lambda_calculus::data::list::pair::map;
+    /// use lambda_calculus::*;
+    ///
+    /// # let directory = std::env::temp_dir().join(format!("blc-store-doc-{}", std::process::id()));
+    /// let store = Store::open(&directory).unwrap();
+    /// let library = store.put(&map()).unwrap();
+    /// let program = store.put(&abs(app(map(), Var(1)))).unwrap();
+    ///
+    /// assert_eq!(store.get(&program), Ok(abs(app(map(), Var(1)))));
+    /// assert!(store.contains(&library));
+    /// # std::fs::remove_dir_all(directory).unwrap();
+    /// ```
+    pub fn put(&self, term: &Term) -> Result<Hash, Error> {
+        let subterms = Subterms::new(term);
+        let hash = subterms.hashes[0];
+        if self.contains(&hash) {
+            return Ok(hash);
+        }
+
+        // a term can contain many copies of a subterm; it's enough to look each of them up once
+        let mut stored = HashMap::new();
+        let mut data = Vec::new();
+        let mut i = 0;
+        while i < subterms.terms.len() {
+            let hash = subterms.hashes[i];
+            if i != 0 && *stored.entry(hash).or_insert_with(|| self.contains(&hash)) {
+                data.push(REF);
+                data.extend_from_slice(&subterms.hashes[i].0);
+                i += subterms.sizes[i];
+                continue;
+            }
+
+            match subterms.terms[i] {
+                Var(n) => {
+                    data.push(VAR);
+                    data.extend_from_slice(&(*n as u64).to_le_bytes());
+                }
+                Abs(_) => data.push(ABS),
+                App(_) => data.push(APP),
+            }
+            i += 1;
+        }
+
+        // a complete object appears at once, so that it is never read partially written
+        let temporary = self.path(&hash).with_extension("tmp");
+        fs::write(&temporary, data)?;
+        fs::rename(temporary, self.path(&hash))?;
+
+        Ok(hash)
+    }
+
+    /// Loads the `Term` with the given hash from the store, substituting its references to other
+    /// stored terms.
+    pub fn get(&self, hash: &Hash) -> Result<Term, Error> {
+        self.load(hash, &mut HashMap::new(), &mut HashSet::new())
+    }
+
+    // loads a term, reusing the already loaded ones; `loading` holds the terms whose loading is in
+    // progress, so that a term referring to itself (directly or not) is detected as corrupt
+    fn load(
+        &self,
+        hash: &Hash,
+        loaded: &mut HashMap<Hash, Term>,
+        loading: &mut HashSet<Hash>,
+    ) -> Result<Term, Error> {
+        enum Frame {
+            Abs,
+            AppLhs,
+            AppRhs(Term),
+        }
+
+        if let Some(term) = loaded.get(hash) {
+            return Ok(term.clone());
+        }
+
+        if !loading.insert(*hash) {
+            return Err(Corrupt(*hash));
+        }
+
+        let data = fs::read(self.path(hash)).map_err(|e| match e.kind() {
+            io::ErrorKind::NotFound => NotFound(*hash),
+            kind => Io(kind),
+        })?;
+
+        let mut stack = Vec::new();
+        let mut rest = &data[..];
+        let term = 'parse: loop {
+            let (&tag, tail) = rest.split_first().ok_or(Corrupt(*hash))?;
+            rest = tail;
+
+            let mut term = match tag {
+                VAR => {
+                    let (index, tail) = rest.split_first_chunk().ok_or(Corrupt(*hash))?;
+                    rest = tail;
+                    Var(u64::from_le_bytes(*index) as usize)
+                }
+                REF => {
+                    let (reference, tail) = rest.split_first_chunk().ok_or(Corrupt(*hash))?;
+                    rest = tail;
+                    self.load(&Hash(*reference), loaded, loading)?
+                }
+                ABS => {
+                    stack.push(Frame::Abs);
+                    continue;
+                }
+                APP => {
+                    stack.push(Frame::AppLhs);
+                    continue;
+                }
+                _ => return Err(Corrupt(*hash)),
+            };
+
+            loop {
+                match stack.pop() {
+                    None => break 'parse term,
+                    Some(Frame::Abs) => term = abs(term),
+                    Some(Frame::AppLhs) => {
+                        stack.push(Frame::AppRhs(term));
+                        break;
+                    }
+                    Some(Frame::AppRhs(lhs)) => term = app(lhs, term),
+                }
+            }
+        };
+
+        if !rest.is_empty() || self::hash(&term) != *hash {
+            return Err(Corrupt(*hash));
+        }
+        loading.remove(hash);
+        loaded.insert(*hash, term.clone());
+
+        Ok(term)
+    }
+
+    fn path(&self, hash: &Hash) -> PathBuf {
+        self.directory.join(hash.to_string())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use lambda_calculus::data::num::church::{add, mul};
+    use std::env;
+
+    #[test]
+    fn stable_hash() {
+        let mut data = vec![VAR];
+        data.extend_from_slice(&1u64.to_le_bytes());
+        let var = Sha256::digest(&data);
+
+        assert_eq!(hash(&Var(1)), Hash(var.into()));
+        assert_eq!(
+            hash(&abs(Var(1))),
+            Hash(Sha256::digest([&[ABS][..], &var].concat()).into())
+        );
+        assert_eq!(
+            hash(&app(Var(1), Var(1))),
+            Hash(Sha256::digest([&[APP][..], &var, &var].concat()).into())
+        );
+        assert_eq!(
+            hash(&Var(1)).to_string(),
+            "51b09ceccfbec44595dd4241e6e2a693d279b72c899c8f60ec63524fe58b1d4f"
+        );
+    }
+
+    #[test]
+    fn hash_strings() {
+        let hash = hash(&abs(Var(1)));
+
+        assert_eq!(hash.to_string().parse(), Ok(hash));
+        assert_eq!("abc".parse::<Hash>(), Err(InvalidHash));
+        assert_eq!("x".repeat(64).parse::<Hash>(), Err(InvalidHash));
+    }
+
+    #[test]
+    fn shared_subterms() {
+        let directory = env::temp_dir().join(format!("blc-store-{}", std::process::id()));
+        let store = Store::open(&directory).unwrap();
+
+        let library = app(add(), mul());
+        let program = abs(app!(Var(1), library.clone(), library.clone()));
+
+        let library_hash = store.put(&library).unwrap();
+        let program_hash = store.put(&program).unwrap();
+        assert_eq!(program_hash, hash(&program));
+        assert_eq!(store.get(&program_hash), Ok(program.clone()));
+
+        // the program refers to the library
+        let size = fs::metadata(store.path(&program_hash)).unwrap().len();
+        assert_eq!(size, 3 + 2 * 33 + 9);
+
+        // so it can't be loaded without it
+        fs::remove_file(store.path(&library_hash)).unwrap();
+        assert_eq!(store.get(&program_hash), Err(NotFound(library_hash)));
+
+        // and the objects are verified
+        fs::write(store.path(&library_hash), [VAR, 2, 0, 0, 0, 0, 0, 0, 0]).unwrap();
+        assert_eq!(store.get(&program_hash), Err(Corrupt(library_hash)));
+        fs::write(store.path(&library_hash), [APP]).unwrap();
+        assert_eq!(store.get(&library_hash), Err(Corrupt(library_hash)));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+
+    #[test]
+    fn reference_cycles() {
+        let directory = env::temp_dir().join(format!("blc-store-cycles-{}", std::process::id()));
+        let store = Store::open(&directory).unwrap();
+        let reference = |hash: &Hash| [&[REF][..], &hash.0].concat();
+
+        // an object referring to itself
+        let term = abs(Var(1));
+        let own = hash(&term);
+        fs::write(store.path(&own), [&[ABS][..], &reference(&own)].concat()).unwrap();
+        assert_eq!(store.get(&own), Err(Corrupt(own)));
+
+        // two objects referring to each other
+        let (first, second) = (hash(&Var(1)), hash(&Var(2)));
+        fs::write(store.path(&first), reference(&second)).unwrap();
+        fs::write(store.path(&second), reference(&first)).unwrap();
+        assert_eq!(store.get(&first), Err(Corrupt(first)));
+
+        fs::remove_dir_all(directory).unwrap();
+    }
+}
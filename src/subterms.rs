@@ -0,0 +1,28 @@
+// the traversal behind the crate's structural hashes
+//
+// There are two of them: `store::hash` (SHA-256) and the cache's (64-bit FNV-1a). The store needs
+// collision-resistant content addresses, but it requires the optional `sha2` dependency, while the
+// cache has to work without any features and compares the terms of the entries it finds anyway,
+// so a cheap hash is enough for it. Both hash a term's subterms in the order given by `preorder`.
+
+use lambda_calculus::*;
+
+// the subterms of a term in preorder; in reverse preorder, the subterms of a term are always
+// visited before it, and the right-hand side of an application at `i` is at `i + 1 + size(i + 1)`
+pub fn preorder(term: &Term) -> Vec<&Term> {
+    let mut terms = Vec::new();
+    let mut stack = vec![term];
+    while let Some(term) = stack.pop() {
+        terms.push(term);
+        match term {
+            Var(_) => {}
+            Abs(t) => stack.push(t),
+            App(boxed) => {
+                stack.push(&boxed.1);
+                stack.push(&boxed.0);
+            }
+        }
+    }
+
+    terms
+}
@@ -0,0 +1,61 @@
+use blc::execution::{Input, Options, run_with_args};
+use blc::*;
+use lambda_calculus::data::list::pair::{append, head, length, tail};
+use lambda_calculus::*;
+
+#[test]
+fn ignoring_args() {
+    let input_blc = to_bits(&abs!(2, Var(1)));
+
+    assert_eq!(
+        run_with_args(
+            &input_blc,
+            &[b"-v"],
+            Input::Bytes(b"herp"),
+            &Options::default()
+        ),
+        Ok("herp".into())
+    );
+}
+
+#[test]
+fn prepending_args() {
+    // λλ. append (head 2) (append (head (tail 2)) 1)
+    let prepend_blc = to_bits(&abs!(
+        2,
+        app!(
+            append(),
+            app(head(), Var(2)),
+            app!(append(), app(head(), app(tail(), Var(2))), Var(1))
+        )
+    ));
+
+    assert_eq!(
+        run_with_args(
+            &prepend_blc,
+            &[b"herp ", b"derp "],
+            Input::Bytes(b"input"),
+            &Options::default()
+        ),
+        Ok("herp derp input".into())
+    );
+}
+
+#[test]
+fn counting_args() {
+    let count_blc = to_bits(&abs(app(length(), Var(1))));
+
+    assert_eq!(
+        run_with_args(
+            &count_blc,
+            &[b"a", b"b", b"c"],
+            Input::Nothing,
+            &Options::default()
+        ),
+        Ok(format!("({:?})", 3.into_church()))
+    );
+    assert_eq!(
+        run_with_args(&count_blc, &[], Input::Nothing, &Options::default()),
+        Ok("".into()) // Church-encoded 0 is the same term as the empty list
+    );
+}
@@ -0,0 +1,35 @@
+mod corpus;
+
+use blc::encoding::bcl::{self, from_lambda, to_bits};
+use blc::execution::Input::Bytes;
+use blc::execution::{Error, Options, run_bcl, run_with};
+use blc::*;
+
+#[test]
+fn corpus_programs() {
+    for program in corpus::programs() {
+        let term = from_bits(&program.blc).unwrap();
+        let bcl = to_bits(&from_lambda(&term).unwrap());
+
+        assert_eq!(bcl::from_bits(&bcl).map(|c| to_bits(&c)), Ok(bcl.clone()));
+
+        for input in program.inputs {
+            assert_eq!(
+                run_bcl(&bcl, Bytes(input), &Options::default()),
+                run_with(&program.blc, Bytes(input), &Options::default()),
+                "{}",
+                program.name
+            );
+        }
+    }
+}
+
+#[test]
+fn invalid_programs() {
+    assert_eq!(
+        run_bcl(b"1 00", Bytes(b""), &Options::default()),
+        Err(Error::InvalidBclProgram(bcl::Error::UnexpectedEof {
+            position: 4
+        }))
+    );
+}
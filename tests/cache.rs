@@ -0,0 +1,89 @@
+mod corpus;
+
+use blc::encoding::lambda::encode;
+use blc::execution::cache::Cache;
+use blc::execution::{Input, Options, run_with};
+use blc::*;
+use lambda_calculus::data::list::pair::{append, reverse};
+use lambda_calculus::*;
+use std::{env, fs};
+
+#[test]
+fn corpus_programs() {
+    let cache = Cache::default();
+    let options = Options {
+        cache: Some(&cache),
+        ..Options::default()
+    };
+
+    for program in corpus::programs() {
+        for input in program.inputs {
+            assert_eq!(
+                run_with(&program.blc, Input::Bytes(input), &options),
+                run(&program.blc, Input::Bytes(input)),
+                "{}",
+                program.name
+            );
+        }
+    }
+}
+
+fn program() -> Vec<u8> {
+    // λ. append (reverse "derp") 1
+    to_bits(&abs(app!(
+        append(),
+        app(reverse(), encode(b"derp")),
+        Var(1)
+    )))
+}
+
+#[test]
+fn in_memory() {
+    let cache = Cache::new();
+    let options = Options {
+        cache: Some(&cache),
+        ..Options::default()
+    };
+
+    assert_eq!(
+        run_with(&program(), Input::Bytes(b"!"), &options),
+        run(&program(), Input::Bytes(b"!"))
+    );
+    assert!(!cache.is_empty());
+
+    let entries = cache.len();
+    assert_eq!(
+        run_with(&program(), Input::Bytes(b"?"), &options),
+        Ok("pred?".into())
+    );
+    assert_eq!(cache.len(), entries);
+}
+
+#[test]
+fn on_disk() {
+    let dir = env::temp_dir().join(format!("blc-cache-test-{}", std::process::id()));
+
+    let cache = Cache::with_directory(&dir).unwrap();
+    let options = Options {
+        cache: Some(&cache),
+        ..Options::default()
+    };
+    assert_eq!(
+        run_with(&program(), Input::Bytes(b"!"), &options),
+        Ok("pred!".into())
+    );
+    assert!(fs::read_dir(&dir).unwrap().count() > 0);
+
+    // a limit this low is only enough to reuse the persisted normal forms
+    let cache = Cache::with_directory(&dir).unwrap().limit(1);
+    let options = Options {
+        cache: Some(&cache),
+        ..Options::default()
+    };
+    assert_eq!(
+        run_with(&program(), Input::Bytes(b"!"), &options),
+        Ok("pred!".into())
+    );
+
+    fs::remove_dir_all(dir).unwrap();
+}
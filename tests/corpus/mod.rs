@@ -0,0 +1,98 @@
+//! The programs used by the integration tests, along with inputs they are known to handle; they
+//! also serve as the seed corpus for differential testing.
+
+#![allow(dead_code)]
+
+use blc::encoding::binary::{from_bytes, to_bits};
+use blc::encoding::lambda::encode;
+use lambda_calculus::data::num::church::{is_zero, rem};
+use lambda_calculus::*;
+
+/// A BLC program along with sample inputs.
+pub struct Program {
+    pub name: &'static str,
+    pub blc: Vec<u8>,
+    pub inputs: &'static [&'static [u8]],
+}
+
+pub const IDENTITY: &[u8] = b" ";
+
+// program code from https://tromp.github.io/cl/Binary_lambda_calculus.html#A_quine
+pub const REPEAT: [u8; 9] = [0x16, 0x46, 0x80, 0x05, 0xbc, 0xbc, 0xfd, 0xf6, 0x80];
+
+// program code from http://www.ioccc.org/2012/tromp/inflate.Blc
+pub const INFLATE: [u8; 30] = [
+    0x44, 0x44, 0x68, 0x16, 0x01, 0x79, 0x1a, 0x00, 0x16, 0x7f, 0xfb, 0xcb, 0xcf, 0xdf, 0x65, 0xfb,
+    0xed, 0x0f, 0x3c, 0xe7, 0x3c, 0xf3, 0xc2, 0xd8, 0x20, 0x58, 0x2c, 0x0b, 0x06, 0xc0,
+];
+
+// program code from http://www.ioccc.org/2012/tromp/deflate.Blc
+pub const DEFLATE: [u8; 34] = [
+    0x44, 0x68, 0x16, 0x05, 0x7e, 0x01, 0x17, 0x00, 0xbe, 0x55, 0xff, 0xf0, 0x0d, 0xc1, 0x8b, 0xb2,
+    0xc1, 0xb0, 0xf8, 0x7c, 0x2d, 0xd8, 0x05, 0x9e, 0x09, 0x7f, 0xbf, 0xb1, 0x48, 0x39, 0xce, 0x81,
+    0xce, 0x80,
+];
+
+pub const SORT: [u8; 55] = [
+    0x15, 0x46, 0x84, 0x06, 0x05, 0x46, 0x81, 0x60, 0x15, 0xfb, 0xec, 0x2f, 0x80, 0x01, 0x5b, 0xf9,
+    0x7f, 0x0b, 0x7e, 0xf7, 0x2f, 0xec, 0x2d, 0xfb, 0x80, 0x56, 0x05, 0xfd, 0x85, 0xbb, 0x76, 0x11,
+    0x5d, 0x50, 0x5c, 0x00, 0xbe, 0x7f, 0xc1, 0x2b, 0xff, 0x0f, 0xfc, 0x2c, 0x1b, 0x72, 0xbf, 0xf0,
+    0xff, 0xc2, 0xc1, 0x6d, 0x34, 0x50, 0x40,
+];
+
+// the bits of a packed program, without the padding that follows it
+fn bits(packed: &[u8]) -> Vec<u8> {
+    to_bits(&from_bytes(packed).unwrap())
+}
+
+/// Returns all the programs of the corpus.
+pub fn programs() -> Vec<Program> {
+    vec![
+        Program {
+            name: "identity",
+            blc: bits(IDENTITY),
+            inputs: &[b"", b"herp derp"],
+        },
+        Program {
+            name: "repeat",
+            blc: bits(&REPEAT),
+            inputs: &[b"", b"hurr"],
+        },
+        Program {
+            name: "inflate",
+            blc: bits(&INFLATE),
+            inputs: &[&[0x1, 0x7a, 0x74]],
+        },
+        Program {
+            name: "deflate",
+            blc: bits(&DEFLATE),
+            inputs: &[b"00000001011110100111010"],
+        },
+        Program {
+            name: "sort",
+            blc: bits(&SORT),
+            inputs: &[b"3241"],
+        },
+    ]
+}
+
+/// Returns a program that performs a single FizzBuzz step on a Church numeral.
+pub fn fizzbuzz() -> Vec<u8> {
+    let fizzbuzz_single = abs(app!(
+        is_zero(),
+        app!(rem(), Var(1), 15.into_church()),
+        encode(b"FizzBuzz"),
+        app!(
+            is_zero(),
+            app!(rem(), Var(1), 3.into_church()),
+            encode(b"Fizz"),
+            app!(
+                is_zero(),
+                app!(rem(), Var(1), 5.into_church()),
+                encode(b"Buzz"),
+                Var(1)
+            )
+        )
+    ));
+    to_bits(&fizzbuzz_single)
+}
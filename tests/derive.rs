@@ -0,0 +1,107 @@
+#![cfg(feature = "derive")]
+
+use blc::encoding::binary::to_bits;
+use blc::encoding::lambda::{LambdaDecode, LambdaEncode, sum, tag, tuple};
+use blc::execution::run_typed;
+use lambda_calculus::*;
+
+#[derive(Debug, Clone, PartialEq, LambdaEncode, LambdaDecode)]
+struct Point {
+    x: u8,
+    y: u8,
+}
+
+#[derive(Debug, Clone, PartialEq, LambdaEncode, LambdaDecode)]
+struct Labelled<T>(String, T);
+
+#[derive(Debug, Clone, PartialEq, LambdaEncode, LambdaDecode)]
+#[lambda(encoding = "transparent")]
+struct Id(u32);
+
+#[derive(Debug, Clone, PartialEq, LambdaEncode, LambdaDecode)]
+struct Unit;
+
+#[derive(Debug, Clone, PartialEq, LambdaEncode, LambdaDecode)]
+enum Expr {
+    Num(u32),
+    Neg(Box<Expr>),
+    Add { lhs: Box<Expr>, rhs: Box<Expr> },
+}
+
+#[derive(Debug, Clone, PartialEq, LambdaEncode, LambdaDecode)]
+#[lambda(encoding = "tagged")]
+enum Shape {
+    Empty,
+    Circle(Point, u8),
+}
+
+fn round_trip<T: LambdaEncode + LambdaDecode + PartialEq + std::fmt::Debug>(value: T) {
+    assert_eq!(T::lambda_decode(value.lambda_encode()), Ok(value));
+}
+
+#[test]
+fn encodings() {
+    let point = Point { x: 1, y: 2 };
+    assert_eq!(
+        point.lambda_encode(),
+        tuple(vec![1.into_church(), 2.into_church()])
+    );
+    assert_eq!(Id(3).lambda_encode(), 3.into_church());
+    assert_eq!(Unit.lambda_encode(), abs(Var(1)));
+    assert_eq!(
+        Expr::Num(4).lambda_encode(),
+        sum(3, 0, vec![4.into_church()])
+    );
+    assert_eq!(Shape::Empty.lambda_encode(), tag(0, vec![]));
+}
+
+#[test]
+fn round_trips() {
+    round_trip(Point { x: 1, y: 2 });
+    round_trip(Labelled(String::from("answer"), vec![Some(42u8)]));
+    round_trip(Id(7));
+    round_trip(Unit);
+    round_trip(Expr::Add {
+        lhs: Box::new(Expr::Num(1)),
+        rhs: Box::new(Expr::Neg(Box::new(Expr::Num(2)))),
+    });
+    round_trip(Shape::Empty);
+    round_trip(Shape::Circle(Point { x: 0, y: 5 }, 3));
+}
+
+#[test]
+fn decoding_errors() {
+    assert!(Point::lambda_decode((1u8, 2u8, 3u8).lambda_encode()).is_err());
+    assert!(Expr::lambda_decode(sum(3, 1, vec![])).is_err());
+    assert!(Shape::lambda_decode(tag(2, vec![])).is_err());
+}
+
+#[test]
+fn evaluating_an_ast() {
+    // a Scott-encoded expression evaluator: eval = Y (λλ. 1 (λ1) (λ. 0) (λλ. + (4 2) (4 1)));
+    // negation saturates at 0, like the subtraction of Church numerals
+    use lambda_calculus::combinators::Y;
+    use lambda_calculus::data::num::church::{add, sub};
+
+    let eval = app(
+        Y(),
+        abs!(
+            2,
+            app!(
+                Var(1),
+                abs(Var(1)),
+                abs(app!(sub(), 0.into_church(), app(Var(3), Var(1)))),
+                abs!(2, app!(add(), app(Var(4), Var(2)), app(Var(4), Var(1))))
+            )
+        ),
+    );
+    let expr = Expr::Add {
+        lhs: Box::new(Expr::Num(40)),
+        rhs: Box::new(Expr::Add {
+            lhs: Box::new(Expr::Num(2)),
+            rhs: Box::new(Expr::Neg(Box::new(Expr::Num(5)))),
+        }),
+    };
+
+    assert_eq!(run_typed(&to_bits(&eval), &expr), Ok(42u32));
+}
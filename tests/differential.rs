@@ -0,0 +1,111 @@
+mod corpus;
+
+use blc::differential::{Evaluator, Strategy, check, evaluators, minimize};
+use blc::encoding::lambda::decode;
+use blc::*;
+use lambda_calculus::*;
+
+const BUDGET: usize = 1 << 14;
+
+#[test]
+fn corpus() {
+    let evaluators = evaluators();
+
+    for program in corpus::programs() {
+        let term = from_bits(&program.blc).unwrap();
+
+        for input in program.inputs {
+            let report = check(&evaluators, &term, Some(input), BUDGET);
+
+            assert!(
+                report.is_consistent(),
+                "{} disagree on {}",
+                report
+                    .disagreements()
+                    .iter()
+                    .map(|outcome| outcome.evaluator.as_str())
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                program.name
+            );
+            assert!(report.outcomes[0].output.is_some(), "{}", program.name);
+        }
+    }
+}
+
+#[test]
+fn fizzbuzz() {
+    let evaluators = evaluators();
+    let fizzbuzz = from_bits(&corpus::fizzbuzz()).unwrap();
+
+    for n in [1, 3, 5, 15] {
+        let report = check(
+            &evaluators,
+            &app(fizzbuzz.clone(), n.into_church()),
+            None,
+            BUDGET,
+        );
+
+        assert!(report.is_consistent(), "disagreement on {}", n);
+    }
+}
+
+// an evaluator that swaps the first two bytes of its output
+struct Swapping;
+
+impl Evaluator for Swapping {
+    fn name(&self) -> String {
+        "swapping".into()
+    }
+
+    fn reduce(&self, term: &mut Term, budget: usize) {
+        term.reduce(NOR, budget);
+        if let Ok(output) = decode(term.clone())
+            && output.len() > 1
+        {
+            let mut bytes = output.into_bytes();
+            bytes.swap(0, 1);
+            *term = blc::encoding::lambda::encode(&bytes);
+        }
+    }
+}
+
+#[test]
+fn minimized_disagreement() {
+    let evaluators: Vec<Box<dyn Evaluator>> = vec![Box::new(Strategy(NOR)), Box::new(Swapping)];
+    let sort = corpus::programs()
+        .into_iter()
+        .find(|program| program.name == "sort")
+        .unwrap();
+    let sort = from_bits(&sort.blc).unwrap();
+
+    assert_eq!(minimize(&evaluators, &sort, b"1111", BUDGET), None);
+    assert_eq!(
+        minimize(&evaluators, &sort, b"3241", BUDGET).map(|input| input.len()),
+        Some(2)
+    );
+}
+
+// an evaluator that can't handle anything
+struct Panicking;
+
+impl Evaluator for Panicking {
+    fn name(&self) -> String {
+        "panicking".into()
+    }
+
+    fn reduce(&self, _term: &mut Term, _budget: usize) {
+        panic!("unsupported term");
+    }
+}
+
+#[test]
+fn panicking_evaluator() {
+    let evaluators: Vec<Box<dyn Evaluator>> = vec![Box::new(Strategy(NOR)), Box::new(Panicking)];
+    let identity = from_bits(b"0010").unwrap();
+    let report = check(&evaluators, &identity, Some(b"herp"), BUDGET);
+
+    assert_eq!(report.outcomes[0].output, Some("herp".into()));
+    assert!(report.outcomes[1].panicked);
+    assert_eq!(report.disagreements(), vec![&report.outcomes[1]]);
+}
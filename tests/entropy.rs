@@ -0,0 +1,37 @@
+mod corpus;
+
+use blc::encoding::binary::compress;
+use blc::encoding::entropy;
+use blc::encoding::lambda::encode;
+use blc::*;
+
+// the ratio of the size of `binary::compress`'s output to the size of `entropy::compress`'s one
+fn ratio(term: &lambda_calculus::Term) -> f64 {
+    let packed = compress(&to_bits(term)).len();
+    let compressed = entropy::compress(term);
+
+    assert_eq!(entropy::decompress(&compressed).as_ref(), Ok(term));
+
+    packed as f64 / compressed.len() as f64
+}
+
+#[test]
+fn corpus_programs() {
+    // hand-optimized programs have little redundancy left; they stay about the same size
+    for program in corpus::programs() {
+        let ratio = ratio(&from_bits(&program.blc).unwrap());
+
+        assert!(ratio > 0.85, "{}: {}", program.name, ratio);
+    }
+}
+
+#[test]
+fn generated_terms() {
+    let text = ratio(&encode(
+        &b"All work and no play makes Jack a dull boy. ".repeat(100),
+    ));
+    assert!(text > 4.0, "text: {}", text);
+
+    let fizzbuzz = ratio(&from_bits(&corpus::fizzbuzz()).unwrap());
+    assert!(fizzbuzz > 1.5, "fizzbuzz: {}", fizzbuzz);
+}
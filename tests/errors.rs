@@ -0,0 +1,89 @@
+use blc::encoding::binary;
+use blc::execution::pipeline::Pipeline;
+use blc::execution::{Error, Input, Options, run_with};
+use blc::*;
+use lambda_calculus::combinators::{I, Y};
+use lambda_calculus::*;
+use std::error::Error as _;
+
+#[test]
+fn invalid_program() {
+    let error = run(b"0100", Input::Nothing).unwrap_err();
+
+    assert_eq!(
+        error,
+        Error::InvalidProgram(binary::Error::UnexpectedEof {
+            position: 4,
+            expected: binary::Token::Term,
+            context: binary::Context::Abstraction
+        })
+    );
+    assert_eq!(
+        error.to_string(),
+        "invalid program: unexpected EOF inside abstraction at bit 4, expected a term"
+    );
+    assert!(error.source().is_some());
+}
+
+#[test]
+fn invalid_argument() {
+    assert_eq!(
+        run(b"0010", Input::Bits(b"0020")),
+        Err(Error::InvalidArgument(binary::Error::InvalidCharacter {
+            position: 2,
+            expected: binary::Token::Term,
+            found: b'2'
+        }))
+    );
+}
+
+#[test]
+fn invalid_output() {
+    // a list containing a list that is not a byte
+    let nil = abs!(2, Var(1));
+    let not_a_byte = abs(app!(Var(1), I(), nil.clone()));
+    let output = run(
+        &to_bits(&abs(app!(Var(1), not_a_byte, nil))),
+        Input::Nothing,
+    );
+
+    assert!(matches!(output, Err(Error::InvalidOutput(_))));
+}
+
+#[test]
+fn limit_exceeded() {
+    let options = Options {
+        limit: 100,
+        ..Options::default()
+    };
+
+    assert_eq!(
+        run_with(&to_bits(&app(Y(), I())), Input::Nothing, &options),
+        Err(Error::LimitExceeded(100))
+    );
+    assert_eq!(
+        run_with(b"0010", Input::Bytes(b"herp"), &options),
+        Ok("herp".into())
+    );
+}
+
+#[test]
+fn pipeline_errors() {
+    let mut pipeline = Pipeline::default();
+
+    assert_eq!(pipeline.push(b"10"), Err(Error::OpenProgram));
+    assert_eq!(
+        pipeline.run_staged(Input::Nothing, &Options::default()),
+        Err(Error::UnsupportedInput)
+    );
+}
+
+#[test]
+fn boxed() {
+    fn parse(blc: &[u8]) -> Result<String, Box<dyn std::error::Error>> {
+        Ok(run(blc, Input::Nothing)?)
+    }
+
+    assert!(parse(b"0010").is_ok());
+    assert!(parse(b"01").is_err());
+}
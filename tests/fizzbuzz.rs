@@ -1,28 +1,13 @@
-use blc::encoding::lambda::encode;
-use blc::execution::Input;
+mod corpus;
+
+use blc::execution::{Input, Options, run_with};
 use blc::*;
-use lambda_calculus::data::num::church::{is_zero, rem};
+use corpus::fizzbuzz;
 use lambda_calculus::*;
 
 #[test]
 fn fizz_buzz() {
-    let fizzbuzz_single = abs(app!(
-        is_zero(),
-        app!(rem(), Var(1), 15.into_church()),
-        encode(b"FizzBuzz"),
-        app!(
-            is_zero(),
-            app!(rem(), Var(1), 3.into_church()),
-            encode(b"Fizz"),
-            app!(
-                is_zero(),
-                app!(rem(), Var(1), 5.into_church()),
-                encode(b"Buzz"),
-                Var(1)
-            )
-        )
-    ));
-    let fizzbuzz_blc = to_bits(&fizzbuzz_single);
+    let fizzbuzz_blc = fizzbuzz();
 
     assert_eq!(
         run(&fizzbuzz_blc, Input::Bits(&to_bits(&1.into_church()))).unwrap(),
@@ -53,3 +38,21 @@ fn fizz_buzz() {
         "FizzBuzz"
     );
 }
+
+#[test]
+fn fizz_buzz_arithmetic() {
+    let fizzbuzz_blc = fizzbuzz();
+    let options = Options {
+        arithmetic: true,
+        ..Options::default()
+    };
+
+    for n in 1..=30 {
+        let input = to_bits(&n.into_church());
+
+        assert_eq!(
+            run_with(&fizzbuzz_blc, Input::Bits(&input), &options),
+            run(&fizzbuzz_blc, Input::Bits(&input))
+        );
+    }
+}
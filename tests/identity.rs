@@ -1,11 +1,13 @@
+mod corpus;
+
 use blc::encoding::binary::decompress;
 use blc::execution::Input;
 use blc::*;
+use corpus::IDENTITY;
 
 #[test]
 fn identity() {
-    let code_compressed = b" ";
-    let code_blc = decompress(code_compressed);
+    let code_blc = decompress(IDENTITY);
 
     assert_eq!(
         run(&code_blc, Input::Bytes(b"herp derp")).unwrap(),
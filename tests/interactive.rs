@@ -0,0 +1,91 @@
+use blc::encoding::lambda::encode_byte;
+use blc::execution::effect::*;
+use blc::execution::{Error, Options};
+use blc::*;
+use lambda_calculus::combinators::Y;
+use lambda_calculus::*;
+
+fn write_all(bytes: &[u8], k: Term) -> Term {
+    bytes.iter().rev().fold(k, |k, &b| write(encode_byte(b), k))
+}
+
+#[test]
+fn cat() {
+    // Y (λself. read (λc. c (λh t _. write c self) (exit 0)))
+    let cat = app(
+        Y(),
+        abs(read(abs(app!(
+            Var(1),
+            abs!(3, write(Var(4), Var(5))),
+            exit(encode_byte(0))
+        )))),
+    );
+
+    let mut output = Vec::new();
+    let code = run_interactive(&to_bits(&cat), &b"herp derp"[..], &mut output, Vec::new());
+
+    assert_eq!(code, Ok(0));
+    assert_eq!(output, b"herp derp");
+}
+
+#[test]
+fn prompt() {
+    let prompt = write_all(
+        b"name? ",
+        read(abs(write_all(
+            b"hi, ",
+            write(Var(1), write_error(Var(1), exit(encode_byte(3)))),
+        ))),
+    );
+
+    let mut output = Vec::new();
+    let mut error = Vec::new();
+    let code = run_interactive(&to_bits(&prompt), &b"x"[..], &mut output, &mut error);
+
+    assert_eq!(code, Ok(3));
+    assert_eq!(output, b"name? hi, x");
+    assert_eq!(error, b"x");
+}
+
+#[test]
+fn not_a_request() {
+    assert_eq!(
+        run_interactive(b"0010", &b""[..], Vec::new(), Vec::new()),
+        Err(Error::InvalidOutput(abs(Var(1))))
+    );
+}
+
+#[test]
+fn reduction_limit() {
+    // Y (write 'y') - writes `y`s forever
+    let yes = app(Y(), abs(write(encode_byte(b'y'), Var(1))));
+    let options = |limit| Options {
+        limit,
+        ..Options::default()
+    };
+
+    let mut output = Vec::new();
+    assert_eq!(
+        run_interactive_with(
+            &to_bits(&yes),
+            &b""[..],
+            &mut output,
+            Vec::new(),
+            &options(1000)
+        ),
+        Err(Error::LimitExceeded(1000))
+    );
+    assert!(!output.is_empty() && output.iter().all(|&b| b == b'y'));
+
+    let hi = write_all(b"hi", exit(encode_byte(0)));
+    assert_eq!(
+        run_interactive_with(
+            &to_bits(&hi),
+            &b""[..],
+            Vec::new(),
+            Vec::new(),
+            &options(1000)
+        ),
+        Ok(0)
+    );
+}
@@ -0,0 +1,135 @@
+use blc::execution::lazy_k::parse;
+use blc::execution::{Error, Input, Options, run_with};
+use blc::*;
+use lambda_calculus::combinators::{I, K, Y};
+use lambda_calculus::data::list::pair::map;
+use lambda_calculus::data::num::church::succ;
+use lambda_calculus::*;
+
+#[test]
+// the empty Lazy K program, i.e. the identity combinator
+fn identity() {
+    assert_eq!(
+        run(&to_bits(&I()), Input::LazyK(b"herp derp")).unwrap(),
+        "herp derp"
+    );
+}
+
+#[test]
+// a constant program, ignoring its input; the output ends with the first 256
+fn constant() {
+    let hi = abs(app!(
+        Var(1),
+        72.into_church(),
+        abs(app!(
+            Var(1),
+            105.into_church(),
+            abs(app!(Var(1), 256.into_church(), I()))
+        ))
+    ));
+
+    assert_eq!(
+        run(&to_bits(&app(K(), hi)), Input::LazyK(b"herp")).unwrap(),
+        "Hi"
+    );
+}
+
+#[test]
+// shifts every byte of the input by one; the terminating 256 becomes 257, which also ends the output
+fn caesar() {
+    let caesar = app(map(), succ());
+
+    assert_eq!(run(&to_bits(&caesar), Input::LazyK(b"HAL")).unwrap(), "IBM");
+}
+
+#[test]
+// Lazy K programs are often written with combinators, producing η-reduced numerals
+fn eta_reduced_numerals() {
+    // K (cons I (cons 256 I))
+    let one = app(
+        K(),
+        abs(app!(Var(1), I(), abs(app!(Var(1), 256.into_church(), I())))),
+    );
+
+    assert_eq!(run(&to_bits(&one), Input::LazyK(b"")).unwrap(), "\u{1}");
+}
+
+#[test]
+// the cat programs given in the Lazy K documentation: the empty program and the identity written
+// in each of the supported syntaxes
+fn documented_cat_programs() {
+    for source in ["", "I", "SKK", "S K K # the identity", "``skk", "(S(K)(K))"] {
+        assert_eq!(
+            run(
+                &to_bits(&parse(source).unwrap()),
+                Input::LazyK(b"herp derp")
+            )
+            .unwrap(),
+            "herp derp",
+            "{:?}",
+            source
+        );
+    }
+}
+
+// the following programs were written for these tests, in the style of the examples that come
+// with Lazy K: combinator sources with the definitions they are built from in comments
+
+// prints "HI" followed by the input
+const GREET: &str = "
+    # B = S(KS)K: B a b x = a (b x)
+    # cons n = S(K(S(SI(Kn))))K: cons n l f = f n l
+    # 2 = S(S(KS)K)I, 3 = S(S(KS)K)2, 72 = B (2 3) (3 2) and 73 = S(S(KS)K)72
+    S(KS)K
+        (S(K(S(SI(K
+            (S(KS)K (S(S(KS)K)I (S(S(KS)K)(S(S(KS)K)I))) (S(S(KS)K)(S(S(KS)K)I) (S(S(KS)K)I)))
+        ))))K)
+        (S(K(S(SI(K(S(S(KS)K)
+            (S(KS)K (S(S(KS)K)I (S(S(KS)K)(S(S(KS)K)I))) (S(S(KS)K)(S(S(KS)K)I) (S(S(KS)K)I)))
+        )))))K)
+";
+
+// shifts every byte of the input by one, like `caesar`
+const SHIFT: &str = "
+    # Y (λm l. cons (succ (car l)) (m (cdr l))), with Y = SSK(S(K(SS(S(SSK))))K)
+    SSK(S(K(SS(S(SSK))))K)
+    (
+        S(K(S(S(KS)(S(K(SI))(S(KK)(S(K(S(S(KS)K)))(SI(KK))))))))
+        (S(K(S(KK)))(S(S(KS)K)(K(SI(K(KI))))))
+    )
+";
+
+#[test]
+fn combinator_sources() {
+    let run_source = |source, input| run(&to_bits(&parse(source).unwrap()), Input::LazyK(input));
+
+    assert_eq!(run_source(GREET, b" there"), Ok("HI there".into()));
+    assert_eq!(run_source(SHIFT, b"HAL"), Ok("IBM".into()));
+    assert_eq!(run_source(SHIFT, b""), Ok("".into()));
+}
+
+#[test]
+// the reductions of all the output count towards the limit
+fn reduction_limit() {
+    let options = Options {
+        limit: 10_000,
+        ..Options::default()
+    };
+    let omega = app(abs(app(Var(1), Var(1))), abs(app(Var(1), Var(1))));
+    // K (Y (cons 65)) - an endless stream of `A`s
+    let endless = app(
+        K(),
+        app(Y(), abs(abs(app!(Var(1), 65.into_church(), Var(2))))),
+    );
+
+    for program in [app(K(), omega), endless] {
+        assert_eq!(
+            run_with(&to_bits(&program), Input::LazyK(b""), &options),
+            Err(Error::LimitExceeded(10_000))
+        );
+    }
+    assert_eq!(
+        run_with(&to_bits(&I()), Input::LazyK(b"herp"), &options),
+        Ok("herp".into())
+    );
+}
@@ -0,0 +1,100 @@
+use blc::encoding::binary::to_bits;
+use blc::encoding::numeral::{Number, Numeral::*};
+use blc::execution::{Options, run_typed, run_typed_with};
+use lambda_calculus::data::num::church::mul;
+use lambda_calculus::data::num::scott;
+use lambda_calculus::{Term::*, abs, app};
+
+#[test]
+fn recognizing_output() {
+    let square = to_bits(&abs(app!(mul(), Var(1), Var(1))));
+    let options = Options {
+        arithmetic: true,
+        ..Options::default()
+    };
+    let n = Number {
+        numeral: Church,
+        value: 30,
+    };
+
+    assert_eq!(
+        run_typed_with(&square, &n, &options),
+        Ok(Number {
+            numeral: Church,
+            value: 900
+        })
+    );
+
+    let successor = to_bits(&scott::succ());
+    let n = Number {
+        numeral: Scott,
+        value: 41,
+    };
+
+    assert_eq!(
+        run_typed(&successor, &n),
+        Ok(Number {
+            numeral: Scott,
+            value: 42
+        })
+    );
+}
+
+#[test]
+fn converting_numerals() {
+    // λ. 1 succ zero - converts a Church numeral to a Scott one
+    let to_scott = to_bits(&abs(app!(Var(1), scott::succ(), scott::zero())));
+    let n = Number {
+        numeral: Church,
+        value: 7,
+    };
+
+    assert_eq!(
+        run_typed(&to_scott, &n),
+        Ok(Number {
+            numeral: Scott,
+            value: 7
+        })
+    );
+}
+
+#[cfg(feature = "num-bigint")]
+#[test]
+fn big_numbers() {
+    use blc::encoding::lambda::LambdaEncode;
+    use blc::encoding::numeral::BigNumber;
+    use lambda_calculus::data::boolean::tru;
+    use num_bigint::BigUint;
+
+    // λ. λ1 0 1 - doubles a binary numeral
+    let double = to_bits(&abs(abs(app!(Var(1), tru(), Var(2)))));
+    let n = BigUint::from(3u8).pow(100);
+
+    assert_eq!(run_typed(&double, &n), Ok(&n * 2u8));
+    assert_eq!(
+        run_typed::<_, BigUint>(
+            &to_bits(&abs(Var(1))),
+            &Number {
+                numeral: Scott,
+                value: 5
+            }
+        ),
+        Ok(BigUint::from(5u8))
+    );
+    assert_eq!(BigUint::from(6u8).lambda_encode(), Binary.encode(6));
+
+    let identity = to_bits(&abs(Var(1)));
+    for numeral in [Church, Scott, Parigot, Binary] {
+        let n = BigNumber {
+            numeral,
+            value: BigUint::from(9u8),
+        };
+        assert_eq!(run_typed(&identity, &n), Ok(n.clone()), "{:?}", numeral);
+        assert_eq!(numeral.decode_big(&numeral.encode(9)), Some(n.value));
+    }
+    assert_eq!(
+        Binary.decode_big(&Binary.encode_big(&(&n << 64u8))),
+        Some(&n << 64u8)
+    );
+    assert_eq!(Church.decode_big(&Binary.encode(6)), None);
+}
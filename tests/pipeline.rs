@@ -0,0 +1,52 @@
+mod corpus;
+
+use blc::encoding::binary::decompress;
+use blc::execution::Input;
+use blc::execution::Options;
+use blc::execution::pipeline::Pipeline;
+use blc::*;
+use corpus::{DEFLATE, INFLATE};
+
+#[test]
+fn inflate_deflate() {
+    let inflate = decompress(&INFLATE);
+    let deflate = decompress(&DEFLATE);
+    let pipeline = Pipeline::new(&[&inflate, &deflate]).unwrap();
+
+    assert_eq!(
+        pipeline
+            .run(Input::Bytes(&[0x1, 0x7a, 0x74]))
+            .unwrap()
+            .as_bytes(),
+        [0x1, 0x7a, 0x74]
+    );
+    assert_eq!(
+        run(&pipeline.to_bits(), Input::Bytes(&[0x1, 0x7a, 0x74])),
+        pipeline.run(Input::Bytes(&[0x1, 0x7a, 0x74]))
+    );
+}
+
+#[test]
+fn staged() {
+    let inflate = decompress(&INFLATE);
+    let deflate = decompress(&DEFLATE);
+    let pipeline = Pipeline::new(&[&inflate, &deflate, &inflate]).unwrap();
+
+    let (output, stats) = pipeline
+        .run_staged(Input::Bytes(&[0x1, 0x7a, 0x74]), &Options::default())
+        .unwrap();
+
+    assert_eq!(output, "000000010111101001110100");
+    assert_eq!(Ok(output), pipeline.run(Input::Bytes(&[0x1, 0x7a, 0x74])));
+    assert_eq!(stats.len(), 3);
+    assert_eq!(stats[0].output_size, stats[2].output_size);
+    assert!(stats.iter().all(|s| s.reductions > 0));
+}
+
+#[test]
+fn empty() {
+    let pipeline = Pipeline::default();
+
+    assert!(pipeline.is_empty());
+    assert_eq!(pipeline.run(Input::Bytes(b"herp")), Ok("herp".into()));
+}
@@ -0,0 +1,127 @@
+mod corpus;
+
+use blc::encoding::binary::{self, compress};
+use blc::encoding::container::{Container, Mode};
+use blc::execution::{Input, Options};
+use blc::program::{Error, Format, Program};
+use lambda_calculus::parser::ParseError::UndefinedFreeVariable;
+use std::{env, fs};
+
+#[test]
+fn detecting_formats() {
+    for program in corpus::programs() {
+        let (expected, format) = Program::from_slice(&program.blc).unwrap();
+        assert_eq!(format, Format::Bits, "{}", program.name);
+
+        let packed = compress(&program.blc);
+        let hex = packed
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect::<Vec<_>>()
+            .join(" ");
+        let container = Container::new(expected.term().clone(), Mode::Bytes)
+            .to_bytes()
+            .unwrap();
+
+        for (data, format) in [
+            (packed, Format::Packed),
+            (hex.into_bytes(), Format::Hex),
+            (container, Format::Container),
+        ] {
+            assert_eq!(
+                Program::from_slice(&data),
+                Ok((expected.clone(), format)),
+                "{}",
+                program.name
+            );
+        }
+    }
+}
+
+#[test]
+fn packed_lookalikes() {
+    let identity = Program::from_bits(b"0010").unwrap();
+
+    // ' ' is 0b00100000, i.e. λ1 when packed, and the bytes following a packed term are ignored
+    for data in [&b" 00"[..], b" 0x00", b" \\", b" \\x.", " λ".as_bytes()] {
+        assert_eq!(
+            Program::from_slice(data),
+            Ok((identity.clone(), Format::Packed)),
+            "{:?}",
+            data
+        );
+        assert_eq!(Format::detect(data), Format::Packed);
+    }
+
+    // data that looks like a hex dump and is a valid one isn't packed
+    assert_eq!(Format::detect(b" 0c"), Format::Hex);
+    // data that isn't valid in any format fails like the first format it looks like it's in
+    assert_eq!(Format::detect(b"00"), Format::Bits);
+    assert_eq!(
+        Program::from_slice(b"\\x.y"),
+        Err(Error::InvalidLambda(UndefinedFreeVariable))
+    );
+}
+
+#[test]
+fn bit_format() {
+    let identity = Program::from_bits(b"0010").unwrap();
+
+    assert_eq!(
+        Program::from_slice(b"00 10\r\n"),
+        Ok((identity.clone(), Format::Bits))
+    );
+    // a form feed isn't whitespace between bits
+    assert_ne!(Format::detect(b"0010\x0c"), Format::Bits);
+    // and a second term isn't ignored
+    assert_eq!(
+        Program::from_slice(b"0010 0010"),
+        Err(Error::Parse(binary::Error::TrailingData {
+            position: 5,
+            found: b'0'
+        }))
+    );
+}
+
+#[test]
+fn lambda_notation() {
+    let (classic, format) = Program::from_slice(b"\\x.\\y.x").unwrap();
+
+    assert_eq!(format, Format::Lambda);
+    assert_eq!(classic, Program::from_bits(b"0000110").unwrap());
+    assert_eq!(
+        Program::from_slice("λ2".as_bytes()),
+        Err(Error::Open(vec![1]))
+    );
+}
+
+#[test]
+fn overriding_formats() {
+    let identity = Program::from_bits(b"0010").unwrap();
+
+    assert_eq!(Program::from_slice_as(b"0010", Format::Bits), Ok(identity));
+    // '0' is 0b00110000, i.e. λ2 when packed
+    assert_eq!(
+        Program::from_slice_as(b"0010", Format::Packed),
+        Err(Error::Open(vec![1]))
+    );
+}
+
+#[test]
+fn loading() {
+    let path = env::temp_dir().join(format!("blc-program-{}.Blc", std::process::id()));
+    fs::write(&path, compress(&corpus::programs()[1].blc)).unwrap();
+
+    let (repeat, format) = Program::load(&path).unwrap();
+    fs::remove_file(&path).unwrap();
+
+    assert_eq!(format, Format::Packed);
+    assert_eq!(
+        repeat.run(Input::Bytes(b"hurr"), &Options::default()),
+        Ok("hurrhurr".into())
+    );
+    assert_eq!(
+        Program::load(&path),
+        Err(Error::Io(std::io::ErrorKind::NotFound))
+    );
+}
@@ -1,12 +1,13 @@
+mod corpus;
+
 use blc::encoding::binary::decompress;
 use blc::execution::Input;
 use blc::*;
+use corpus::REPEAT;
 
 #[test]
-// program code from https://tromp.github.io/cl/Binary_lambda_calculus.html#A_quine
 fn repeat() {
-    let code_compressed = [0x16, 0x46, 0x80, 0x05, 0xbc, 0xbc, 0xfd, 0xf6, 0x80];
-    let code_blc = decompress(&code_compressed);
+    let code_blc = decompress(&REPEAT);
 
     assert_eq!(run(&code_blc, Input::Bytes(b"hurr")).unwrap(), "hurrhurr");
 }
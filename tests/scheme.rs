@@ -0,0 +1,109 @@
+mod corpus;
+
+use blc::encoding::binary::to_bits;
+use blc::encoding::lambda::Error::Unexpected;
+use blc::encoding::scheme::{Byte, List, Scheme};
+use blc::execution::Input::Bytes;
+use blc::execution::{Error, Options, run_with, run_with_args};
+use lambda_calculus::data::boolean::tru;
+use lambda_calculus::data::list::scott;
+use lambda_calculus::{IntoChurchNum, Term::*, abs, app};
+
+#[test]
+fn scott_lists_of_numerals() {
+    let scheme = Scheme {
+        list: List::Scott,
+        byte: Byte::Numeral,
+    };
+    let options = Options {
+        encoding: Some(&scheme),
+        ..Options::default()
+    };
+
+    // λ. cons 72 1 - prepends an 'H'
+    let prepend = to_bits(&abs(app!(scott::cons(), 72.into_church(), Var(1))));
+    assert_eq!(
+        run_with(&prepend, Bytes(b"erp"), &options),
+        Ok("Herp".into())
+    );
+}
+
+#[test]
+fn church_lists() {
+    let scheme = Scheme {
+        list: List::Church,
+        ..Scheme::default()
+    };
+    let options = Options {
+        encoding: Some(&scheme),
+        ..Options::default()
+    };
+
+    // λλλ. 3 (3 2 1) 1 - appends a list to itself
+    let twice = to_bits(&abs!(3, app!(Var(3), app!(Var(3), Var(2), Var(1)), Var(1))));
+    // λλ. 2 1 (λλ2) - returns the first argument
+    let first_arg = to_bits(&abs!(2, app!(Var(2), Var(1), tru())));
+
+    assert_eq!(
+        run_with(&twice, Bytes(b"hurr"), &options),
+        Ok("hurrhurr".into())
+    );
+    assert_eq!(
+        run_with_args(&first_arg, &[b"herp", b"derp"], Bytes(b""), &options),
+        Ok("herp".into())
+    );
+}
+
+#[test]
+fn bit_conventions() {
+    for byte in [
+        Byte::Bits {
+            lsb_first: true,
+            inverted: false,
+        },
+        Byte::Bits {
+            lsb_first: false,
+            inverted: true,
+        },
+    ] {
+        let scheme = Scheme {
+            byte,
+            ..Scheme::default()
+        };
+        let options = Options {
+            encoding: Some(&scheme),
+            ..Options::default()
+        };
+
+        // these don't look into the bytes, so they work with any of their encodings
+        let programs = corpus::programs().into_iter();
+        for program in programs.filter(|p| ["identity", "repeat"].contains(&p.name)) {
+            for input in program.inputs {
+                assert_eq!(
+                    run_with(&program.blc, Bytes(input), &options),
+                    run_with(&program.blc, Bytes(input), &Options::default()),
+                    "{}",
+                    program.name
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn decoding_errors() {
+    let scheme = Scheme {
+        list: List::Scott,
+        ..Scheme::default()
+    };
+    let options = Options {
+        encoding: Some(&scheme),
+        ..Options::default()
+    };
+
+    // λλλ1 - returns an empty single-pair list
+    assert_eq!(
+        run_with(b"00000010", Bytes(b""), &options),
+        Err(Error::from(Unexpected("list", abs!(2, Var(1)))))
+    );
+}
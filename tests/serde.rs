@@ -0,0 +1,62 @@
+#![cfg(feature = "serde")]
+
+mod corpus;
+
+use blc::from_bits;
+use blc::program::Program;
+use blc::serialization::{Bits, Tree};
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::fmt;
+
+// checks that a value survives serialization to JSON and to bincode
+fn round_trip<T>(value: T, name: &str)
+where
+    T: Serialize + DeserializeOwned + PartialEq + fmt::Debug,
+{
+    let json = serde_json::to_string(&value).unwrap();
+    assert_eq!(
+        serde_json::from_str::<T>(&json).as_ref().ok(),
+        Some(&value),
+        "{}",
+        name
+    );
+
+    let binary = bincode::serialize(&value).unwrap();
+    assert_eq!(
+        bincode::deserialize::<T>(&binary).ok(),
+        Some(value),
+        "{}",
+        name
+    );
+}
+
+#[test]
+fn corpus_programs() {
+    for program in corpus::programs() {
+        let parsed = Program::from_bits(&program.blc).unwrap();
+        let term = parsed.term().clone();
+
+        round_trip(parsed, program.name);
+        round_trip(Bits(term.clone()), program.name);
+        round_trip(Tree(term), program.name);
+    }
+}
+
+#[test]
+fn fizzbuzz() {
+    round_trip(Program::from_bits(&corpus::fizzbuzz()).unwrap(), "fizzbuzz");
+}
+
+#[test]
+fn invalid_data() {
+    assert!(serde_json::from_str::<Bits>(r#""01""#).is_err());
+    assert!(serde_json::from_str::<Bits>(r#""00101""#).is_err()); // trailing data
+    assert!(serde_json::from_str::<Program>(r#""00110""#).is_err()); // not closed
+    assert!(serde_json::from_str::<Tree>(r#"{"Lam":{"Var":1}}"#).is_err());
+
+    assert_eq!(
+        serde_json::from_str::<Bits>(r#""0010""#).ok(),
+        Some(Bits(from_bits(b"0010").unwrap()))
+    );
+}
@@ -1,16 +1,13 @@
+mod corpus;
+
 use blc::encoding::binary::decompress;
 use blc::execution::Input;
 use blc::*;
+use corpus::SORT;
 
 #[test]
 fn sort() {
-    let code_compressed = [
-        0x15, 0x46, 0x84, 0x06, 0x05, 0x46, 0x81, 0x60, 0x15, 0xfb, 0xec, 0x2f, 0x80, 0x01, 0x5b,
-        0xf9, 0x7f, 0x0b, 0x7e, 0xf7, 0x2f, 0xec, 0x2d, 0xfb, 0x80, 0x56, 0x05, 0xfd, 0x85, 0xbb,
-        0x76, 0x11, 0x5d, 0x50, 0x5c, 0x00, 0xbe, 0x7f, 0xc1, 0x2b, 0xff, 0x0f, 0xfc, 0x2c, 0x1b,
-        0x72, 0xbf, 0xf0, 0xff, 0xc2, 0xc1, 0x6d, 0x34, 0x50, 0x40,
-    ];
-    let code_blc = decompress(&code_compressed);
+    let code_blc = decompress(&SORT);
 
     assert_eq!(run(&code_blc, Input::Bytes(b"3241")).unwrap(), "1234");
 }
@@ -0,0 +1,41 @@
+#![cfg(feature = "store")]
+
+mod corpus;
+
+use blc::from_bits;
+use blc::store::{Store, hash};
+use std::{env, fs};
+
+#[test]
+fn corpus_programs() {
+    let directory = env::temp_dir().join(format!("blc-store-corpus-{}", std::process::id()));
+    let store = Store::open(&directory).unwrap();
+
+    let hashes = corpus::programs()
+        .iter()
+        .map(|program| store.put(&from_bits(&program.blc).unwrap()).unwrap())
+        .collect::<Vec<_>>();
+
+    // a program built from the others refers to them
+    let terms = corpus::programs()
+        .iter()
+        .map(|program| from_bits(&program.blc).unwrap())
+        .collect::<Vec<_>>();
+    let combined = terms.iter().cloned().reduce(lambda_calculus::app).unwrap();
+    let combined_hash = store.put(&combined).unwrap();
+
+    let reopened = Store::open(&directory).unwrap();
+    for (term, hash) in terms.iter().zip(&hashes) {
+        assert_eq!(reopened.get(hash).as_ref(), Ok(term));
+    }
+    assert_eq!(reopened.get(&combined_hash), Ok(combined.clone()));
+    assert_eq!(combined_hash, hash(&combined));
+    assert!(
+        fs::metadata(directory.join(combined_hash.to_string()))
+            .unwrap()
+            .len()
+            < 200
+    );
+
+    fs::remove_dir_all(directory).unwrap();
+}
@@ -0,0 +1,78 @@
+#![cfg(feature = "tracing")]
+
+use blc::execution::Input;
+use blc::*;
+use lambda_calculus::data::num::church::{is_even, pow};
+use lambda_calculus::*;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+#[derive(Default)]
+struct Recorded {
+    spans: Mutex<Vec<&'static str>>,
+    events: Mutex<Vec<&'static str>>,
+    ids: AtomicU64,
+}
+
+#[derive(Clone, Default)]
+struct Recorder(Arc<Recorded>);
+
+impl Subscriber for Recorder {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+
+    fn new_span(&self, span: &Attributes) -> Id {
+        self.0.spans.lock().unwrap().push(span.metadata().name());
+        Id::from_u64(self.0.ids.fetch_add(1, Ordering::Relaxed) + 1)
+    }
+
+    fn record(&self, _: &Id, _: &Record) {}
+
+    fn record_follows_from(&self, _: &Id, _: &Id) {}
+
+    fn event(&self, event: &Event) {
+        let message = event.metadata().fields().field("message").map(|_| "event");
+        self.0.events.lock().unwrap().extend(message);
+    }
+
+    fn enter(&self, _: &Id) {}
+
+    fn exit(&self, _: &Id) {}
+}
+
+#[test]
+fn spans_and_events() {
+    let recorder = Recorder::default();
+    // is_even (pow 2 12) takes ~20k reductions
+    let program = to_bits(&app(
+        is_even(),
+        app!(pow(), 2.into_church(), 12.into_church()),
+    ));
+
+    let output = tracing::subscriber::with_default(recorder.clone(), || {
+        run(&program, Input::Bytes(b"herp"))
+    });
+
+    assert!(output.is_ok());
+    assert_eq!(
+        *recorder.0.spans.lock().unwrap(),
+        ["from_bits", "run", "encode"]
+    );
+    assert!(!recorder.0.events.lock().unwrap().is_empty());
+}
+
+#[test]
+fn typed_runs() {
+    let recorder = Recorder::default();
+    let identity = to_bits(&abs(Var(1)));
+
+    let output = tracing::subscriber::with_default(recorder.clone(), || {
+        execution::run_typed::<_, u8>(&identity, &3u8)
+    });
+
+    assert_eq!(output, Ok(3));
+    assert_eq!(*recorder.0.spans.lock().unwrap(), ["from_bits", "run"]);
+}
@@ -0,0 +1,71 @@
+use blc::encoding::binary::to_bits;
+use blc::execution::{Error, Options, run_typed, run_typed_with};
+use lambda_calculus::data::list::pair::{foldl, length, map, reverse};
+use lambda_calculus::data::num::church::add;
+use lambda_calculus::*;
+
+#[test]
+fn numbers() {
+    let sum = to_bits(&abs(app!(foldl(), add(), 0.into_church(), Var(1))));
+    let options = Options {
+        arithmetic: true,
+        ..Options::default()
+    };
+
+    assert_eq!(run_typed(&sum, &vec![1u64, 2, 3, 4]), Ok(10u64));
+    assert_eq!(
+        run_typed_with(&sum, &vec![100u64; 10], &options),
+        Ok(1000u64)
+    );
+}
+
+#[test]
+fn strings_and_lists() {
+    let reverse = to_bits(&reverse());
+    let length = to_bits(&length());
+
+    assert_eq!(
+        run_typed(&reverse, &String::from("herp")),
+        Ok(String::from("preh"))
+    );
+    assert_eq!(
+        run_typed(&reverse, &vec![Some(true), None]),
+        Ok(vec![None, Some(true)])
+    );
+    assert_eq!(run_typed(&length, &vec![(1u8, 2u8); 5]), Ok(5usize));
+}
+
+#[test]
+fn pairs() {
+    // λ. 1 (λλ. λ1 (map (λ. 1 4 0) 2) 2) - pairs the mapped booleans with the number
+    let program = abs(app(
+        Var(1),
+        abs!(
+            2,
+            abs(app!(
+                Var(1),
+                app!(map(), abs(app!(Var(1), Var(4), 0.into_church())), Var(2)),
+                Var(3)
+            ))
+        ),
+    ));
+
+    assert_eq!(
+        run_typed::<(u32, Vec<bool>), (Vec<u32>, u32)>(&to_bits(&program), &(7, vec![false, true])),
+        Ok((vec![0, 7], 7))
+    );
+}
+
+#[test]
+fn unexpected_output() {
+    let identity = to_bits(&abs(Var(1)));
+
+    assert_eq!(
+        run_typed::<bool, u8>(&identity, &false),
+        Ok(0) // Church false is Church 0
+    );
+    assert_eq!(
+        run_typed::<bool, u8>(&identity, &true),
+        Err(Error::InvalidOutput(true.into()))
+    );
+}